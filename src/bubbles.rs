@@ -0,0 +1,287 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::gfa::gfa::GFAtk;
+use crate::gfa::gfa_string;
+use crate::gfa::graph::{segments_subgraph, Bubble, SortOrder};
+use crate::load::{load_gfa, load_gfa_stdin};
+use crate::utils;
+use anyhow::{bail, Context, Result};
+use gfa::gfa::Orientation;
+use petgraph::graph::NodeIndex;
+
+/// A single path through a bubble, from its entry to its exit.
+#[derive(Clone, Debug)]
+pub struct Branch {
+    /// The interior oriented segments, in walk order. Empty if the
+    /// entry links directly to the exit.
+    pub segments: Vec<(Vec<u8>, Orientation)>,
+    /// The sequence length between the entry and exit, with the
+    /// overlaps of the links walked removed.
+    pub length: usize,
+    /// The length weighted coverage of the interior segments.
+    pub coverage: f32,
+}
+
+impl Branch {
+    /// The path notation of the branch, e.g. `2+,3-`.
+    pub fn path_string(&self) -> String {
+        self.segments
+            .iter()
+            .map(|(name, orientation)| format!("{}{}", String::from_utf8_lossy(name), orientation))
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+}
+
+/// A bubble in a GFA, with segment names, and its branches.
+#[derive(Clone, Debug)]
+pub struct BubbleReport {
+    /// The oriented segment the bubble is entered from.
+    pub entry: (Vec<u8>, Orientation),
+    /// The oriented segment the bubble exits to.
+    pub exit: (Vec<u8>, Orientation),
+    /// Whether the bubble is a simple bubble, otherwise a superbubble.
+    pub simple: bool,
+    /// The names of the interior segments.
+    pub segments: Vec<Vec<u8>>,
+    /// The branches through the bubble.
+    pub branches: Vec<Branch>,
+}
+
+impl BubbleReport {
+    /// The index of the branch with the highest coverage, the first on a tie.
+    pub fn best_branch(&self) -> Option<usize> {
+        let mut best: Option<usize> = None;
+        for (index, branch) in self.branches.iter().enumerate() {
+            if best.is_none_or(|b| branch.coverage > self.branches[b].coverage) {
+                best = Some(index);
+            }
+        }
+        best
+    }
+}
+
+/// Find the bubbles of a GFA, and enumerate up to `max_branches` branches through each.
+///
+/// Bubbles are in the order they are found, with no more than `max_size`
+/// oriented segments searched from each entry.
+pub fn find_bubbles(
+    gfa: &GFAtk,
+    max_size: usize,
+    max_branches: usize,
+) -> Result<Vec<BubbleReport>> {
+    let (_, gfa_graph) = gfa.into_digraph()?;
+    let overlaps = gfa.link_overlaps()?;
+
+    let mut len_cov: HashMap<Vec<u8>, (usize, f32)> = HashMap::new();
+    for segment in &gfa.0.segments {
+        let stat = gfa.node_seq_len_and_cov(segment.name.clone())?;
+        len_cov.insert(segment.name.clone(), stat);
+    }
+
+    let name = |node: NodeIndex| gfa_graph.0[node].clone();
+
+    let mut reports = Vec::new();
+    for bubble in gfa_graph.superbubbles(max_size) {
+        let Bubble {
+            entry,
+            exit,
+            ref interior,
+            simple,
+        } = bubble;
+
+        let mut branches = Vec::new();
+        for (path, edges) in gfa_graph.bubble_branches(&bubble, max_branches) {
+            let mut length = 0;
+            let mut weighted_coverage = 0.0;
+            for (node, _) in &path {
+                let (l, c) = len_cov
+                    .get(&name(*node))
+                    .copied()
+                    .context("Segment in a bubble not found in GFA.")?;
+                length += l;
+                weighted_coverage += c * l as f32;
+            }
+            let coverage = if length == 0 {
+                0.0
+            } else {
+                weighted_coverage / length as f32
+            };
+            let overlap: usize = edges.iter().map(|e| overlaps[e.index()]).sum();
+
+            branches.push(Branch {
+                segments: path.iter().map(|(n, o)| (name(*n), *o)).collect(),
+                length: length.saturating_sub(overlap),
+                coverage,
+            });
+        }
+
+        let mut segments: Vec<Vec<u8>> = interior.iter().map(|(n, _)| name(*n)).collect();
+        segments.sort_by(|a, b| utils::natural_cmp(a, b));
+
+        reports.push(BubbleReport {
+            entry: (name(entry.0), entry.1),
+            exit: (name(exit.0), exit.1),
+            simple,
+            segments,
+            branches,
+        });
+    }
+
+    Ok(reports)
+}
+
+/// Pop bubbles, keeping only the highest coverage branch of each.
+///
+/// Larger bubbles are popped first, and a bubble which shares segments with
+/// one already popped is left alone. Returns the segments to remove.
+pub fn pop_bubbles(bubbles: &[BubbleReport]) -> HashSet<Vec<u8>> {
+    let mut order: Vec<&BubbleReport> = bubbles.iter().collect();
+    order.sort_by_key(|b| std::cmp::Reverse(b.segments.len()));
+
+    let mut touched: HashSet<Vec<u8>> = HashSet::new();
+    let mut removed = HashSet::new();
+
+    for bubble in order {
+        let touches = bubble
+            .segments
+            .iter()
+            .chain([&bubble.entry.0, &bubble.exit.0])
+            .any(|s| touched.contains(s));
+        if touches {
+            continue;
+        }
+        let best = match bubble.best_branch() {
+            Some(b) => b,
+            None => continue,
+        };
+        let keep: HashSet<&Vec<u8>> = bubble.branches[best]
+            .segments
+            .iter()
+            .map(|(name, _)| name)
+            .collect();
+
+        for segment in &bubble.segments {
+            if !keep.contains(segment) {
+                removed.insert(segment.clone());
+            }
+        }
+        touched.extend(bubble.segments.iter().cloned());
+        touched.insert(bubble.entry.0.clone());
+        touched.insert(bubble.exit.0.clone());
+    }
+
+    removed
+}
+
+/// Find simple bubbles and superbubbles in a GFA.
+///
+/// Prints a TSV of each bubble's entry and exit segments, and the paths, lengths
+/// and coverages of its branches. Optionally pops the bubbles, keeping the highest
+/// coverage branch, and writes the cleaned GFA to `--pop`.
+///
+/// For example:
+/// ```bash
+/// gfatk bubbles in.gfa --pop popped.gfa > bubbles.tsv
+/// ```
+pub fn bubbles(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let max_size = *matches
+        .get_one::<usize>("max-bubble-size")
+        .expect("defaulted by clap");
+    let max_branches = *matches
+        .get_one::<usize>("max-branches")
+        .expect("defaulted by clap");
+    let pop_file = matches.get_one::<PathBuf>("pop");
+
+    let gfa: GFAtk = match gfa_file {
+        Some(f) => {
+            let ext = f.extension();
+            match ext {
+                Some(e) => {
+                    if e == "gfa" {
+                        GFAtk(load_gfa(f)?)
+                    } else {
+                        bail!("Input is not a GFA.")
+                    }
+                }
+                None => bail!("Could not read file."),
+            }
+        }
+        None => match utils::is_stdin() {
+            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            false => bail!("No input from STDIN. Run `gfatk bubbles -h` for help."),
+        },
+    };
+
+    let bubbles = find_bubbles(&gfa, max_size, max_branches)?;
+
+    eprintln!("[+]\tFound {} bubble(s).", bubbles.len());
+
+    println!("bubble\ttype\tentry\texit\tbranches\tbranch_lengths\tbranch_coverages\tsegments");
+
+    for (index, bubble) in bubbles.iter().enumerate() {
+        let branches: Vec<String> = bubble.branches.iter().map(|b| b.path_string()).collect();
+        let lengths: Vec<String> = bubble
+            .branches
+            .iter()
+            .map(|b| b.length.to_string())
+            .collect();
+        let coverages: Vec<String> = bubble
+            .branches
+            .iter()
+            .map(|b| b.coverage.to_string())
+            .collect();
+        let segments: Vec<String> = bubble
+            .segments
+            .iter()
+            .map(|s| String::from_utf8_lossy(s).to_string())
+            .collect();
+
+        println!(
+            "{}\t{}\t{}{}\t{}{}\t{}\t{}\t{}\t{}",
+            index,
+            if bubble.simple {
+                "simple"
+            } else {
+                "superbubble"
+            },
+            String::from_utf8_lossy(&bubble.entry.0),
+            bubble.entry.1,
+            String::from_utf8_lossy(&bubble.exit.0),
+            bubble.exit.1,
+            branches.join(";"),
+            lengths.join(","),
+            coverages.join(","),
+            segments.join(",")
+        );
+    }
+
+    if let Some(pop_file) = pop_file {
+        let removed = pop_bubbles(&bubbles);
+        let keep: Vec<Vec<u8>> = gfa
+            .0
+            .segments
+            .iter()
+            .filter(|s| !removed.contains(&s.name))
+            .map(|s| s.name.clone())
+            .collect();
+
+        let popped = segments_subgraph(&gfa.0, keep);
+        fs::write(
+            pop_file,
+            gfa_string(&popped, SortOrder::from_matches(matches)),
+        )
+        .with_context(|| format!("Could not write {:?}", pop_file.as_os_str()))?;
+
+        eprintln!(
+            "[+]\tRemoved {} segment(s) popping bubbles, written to {:?}",
+            removed.len(),
+            pop_file.as_os_str()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,274 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::gfa::gfa::GFAtk;
+use crate::gfa::gfa_string;
+use crate::gfa::graph::SortOrder;
+use crate::load::{load_gfa, load_gfa_stdin};
+use crate::utils::{self, parse_cigar};
+use anyhow::{bail, Context, Result};
+use gfa::cigar::{CIGAROp, CIGAR};
+use gfa::gfa::{Link, Orientation, Path, Segment, GFA};
+use gfa::optfields::{OptField, OptFieldVal};
+
+/// A piece of a chopped segment.
+#[derive(Clone, Debug)]
+pub struct Piece {
+    /// The original segment name.
+    pub segment: Vec<u8>,
+    /// The name of the piece. Unchopped segments keep their name.
+    pub name: Vec<u8>,
+    /// The start of the piece in the original segment (0-based).
+    pub start: usize,
+    /// The end of the piece in the original segment (exclusive).
+    pub end: usize,
+}
+
+/// Split the segments of a GFA longer than `max_len` into pieces of (nearly) equal length,
+/// none longer than `max_len`.
+///
+/// Pieces are named `<segment>_<n>`, from 1, and joined in order by `0M` links, with
+/// edge coverage from the segment coverage (and mirrored, if the input links are).
+/// The optional fields of a segment, such as coverage, are copied to each of its
+/// pieces. Links are rewired to the first or last piece, and the steps of P lines
+/// expanded to every piece. Returns the new GFA, and the [`Piece`]s of every segment
+/// in GFA order.
+pub fn chop_gfa(gfa: &GFAtk, max_len: usize) -> Result<(GFAtk, Vec<Piece>)> {
+    if max_len == 0 {
+        bail!("--max-len must be greater than zero.");
+    }
+    let names: HashSet<&[u8]> = gfa.0.segments.iter().map(|s| s.name.as_slice()).collect();
+    let mirrored = gfa.normalize_links(false)?.1.mirror_pairs > 0;
+
+    let mut segments = Vec::new();
+    let mut links = Vec::new();
+    let mut pieces = Vec::new();
+    // the pieces of each segment, in forward order
+    let mut pieces_of: HashMap<&[u8], Vec<Vec<u8>>> = HashMap::new();
+
+    for segment in &gfa.0.segments {
+        let length = segment.sequence.len();
+        let count = length.div_ceil(max_len).max(1);
+
+        let mut piece_names = Vec::new();
+        let mut start = 0;
+        for index in 0..count {
+            // spread the remainder over the first pieces
+            let end = start + length / count + usize::from(index < length % count);
+            let name = match count {
+                1 => segment.name.clone(),
+                _ => {
+                    let name = format!("{}_{}", String::from_utf8_lossy(&segment.name), index + 1)
+                        .into_bytes();
+                    if names.contains(name.as_slice()) {
+                        bail!(
+                            "Chopped segment name {} is already in the GFA.",
+                            String::from_utf8_lossy(&name)
+                        );
+                    }
+                    name
+                }
+            };
+
+            segments.push(Segment {
+                name: name.clone(),
+                sequence: segment.sequence[start..end].to_vec(),
+                optional: segment.optional.clone(),
+            });
+            pieces.push(Piece {
+                segment: segment.name.clone(),
+                name: name.clone(),
+                start,
+                end,
+            });
+            piece_names.push(name);
+            start = end;
+        }
+
+        // join the pieces
+        let ec = gfa
+            .node_seq_len_and_cov(segment.name.clone())
+            .ok()
+            .map(|(_, coverage)| OptField::new(b"ec", OptFieldVal::Int(coverage.round() as i64)));
+        for pair in piece_names.windows(2) {
+            let link = |from: &[u8], orient: Orientation, to: &[u8]| Link {
+                from_segment: from.to_vec(),
+                from_orient: orient,
+                to_segment: to.to_vec(),
+                to_orient: orient,
+                overlap: b"0M".to_vec(),
+                optional: ec.iter().cloned().collect(),
+            };
+            links.push(link(&pair[0], Orientation::Forward, &pair[1]));
+            if mirrored {
+                links.push(link(&pair[1], Orientation::Backward, &pair[0]));
+            }
+        }
+
+        pieces_of.insert(&segment.name, piece_names);
+    }
+
+    let piece_lengths: HashMap<&[u8], usize> = pieces
+        .iter()
+        .map(|p| (p.name.as_slice(), p.end - p.start))
+        .collect();
+
+    // leaving a segment forward is leaving its last piece, and entering
+    // forward is entering its first, and the reverse in reverse
+    let end_piece = |name: &[u8], last: bool| -> Result<&[u8]> {
+        let p = pieces_of.get(name).with_context(|| {
+            format!(
+                "Segment {} not found in GFA.",
+                String::from_utf8_lossy(name)
+            )
+        })?;
+        Ok(if last { &p[p.len() - 1] } else { &p[0] })
+    };
+
+    for link in &gfa.0.links {
+        let from = end_piece(&link.from_segment, link.from_orient == Orientation::Forward)?;
+        let to = end_piece(&link.to_segment, link.to_orient == Orientation::Backward)?;
+
+        let overlap = parse_cigar(&link.overlap)?;
+        if overlap > piece_lengths[from] || overlap > piece_lengths[to] {
+            bail!(
+                "The overlap of link {}{} -> {}{} ({}bp) is longer than the chopped pieces. Increase --max-len.",
+                String::from_utf8_lossy(&link.from_segment),
+                link.from_orient,
+                String::from_utf8_lossy(&link.to_segment),
+                link.to_orient,
+                overlap
+            );
+        }
+
+        links.push(Link {
+            from_segment: from.to_vec(),
+            to_segment: to.to_vec(),
+            ..link.clone()
+        });
+    }
+
+    let mut paths = Vec::new();
+    for path in &gfa.0.paths {
+        let mut steps = Vec::new();
+        let mut overlaps = Vec::new();
+        for (index, (name, orientation)) in path.iter().enumerate() {
+            if index > 0 {
+                overlaps.push(path.overlaps.get(index - 1).cloned().flatten());
+            }
+            let mut names = pieces_of
+                .get(name.as_ref())
+                .with_context(|| {
+                    format!(
+                        "Segment {} in path {} not found in GFA.",
+                        name,
+                        String::from_utf8_lossy(&path.path_name)
+                    )
+                })?
+                .clone();
+            if orientation == Orientation::Backward {
+                names.reverse();
+            }
+            for (piece_index, piece) in names.iter().enumerate() {
+                if piece_index > 0 {
+                    overlaps.push(Some(CIGAR::from_pairs([(0, CIGAROp::M)])));
+                }
+                steps.push(format!("{}{}", String::from_utf8_lossy(piece), orientation));
+            }
+        }
+        // keep paths without overlaps (`*`) without them
+        if path.overlaps.is_empty() {
+            overlaps.clear();
+        }
+        paths.push(Path::new(
+            path.path_name.clone(),
+            steps.join(",").into_bytes(),
+            overlaps,
+            path.optional.clone(),
+        ));
+    }
+
+    Ok((
+        GFAtk(GFA {
+            header: gfa.0.header.clone(),
+            segments,
+            links,
+            paths,
+            containments: gfa.0.containments.clone(),
+        }),
+        pieces,
+    ))
+}
+
+/// Chop the segments of a GFA into pieces no longer than `--max-len`.
+///
+/// The chopped GFA is printed to STDOUT, and optionally a TSV mapping each original
+/// segment to its pieces (with their coordinates in the original segment) is written
+/// to `--mapping`.
+///
+/// For example:
+/// ```bash
+/// gfatk chop in.gfa --max-len 1000 > out.gfa
+/// ```
+pub fn chop(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let max_len = *matches
+        .get_one::<usize>("max-len")
+        .expect("required by clap");
+    let mapping_file = matches.get_one::<PathBuf>("mapping");
+
+    let gfa: GFAtk = match gfa_file {
+        Some(f) => {
+            let ext = f.extension();
+            match ext {
+                Some(e) => {
+                    if e == "gfa" {
+                        GFAtk(load_gfa(f)?)
+                    } else {
+                        bail!("Input is not a GFA.")
+                    }
+                }
+                None => bail!("Could not read file."),
+            }
+        }
+        None => match utils::is_stdin() {
+            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            false => bail!("No input from STDIN. Run `gfatk chop -h` for help."),
+        },
+    };
+
+    if !gfa.0.containments.is_empty() {
+        eprintln!("[-]\tContainment (C) lines are carried over unchanged.");
+    }
+
+    let (chopped, pieces) = chop_gfa(&gfa, max_len)?;
+
+    eprintln!(
+        "[+]\tChopped {} segments into {} segments.",
+        gfa.0.segments.len(),
+        chopped.0.segments.len()
+    );
+
+    if let Some(mapping_file) = mapping_file {
+        let mut mapping_tsv = String::from("segment\tpiece\tstart\tend\n");
+        for piece in &pieces {
+            mapping_tsv += &format!(
+                "{}\t{}\t{}\t{}\n",
+                String::from_utf8_lossy(&piece.segment),
+                String::from_utf8_lossy(&piece.name),
+                piece.start,
+                piece.end
+            );
+        }
+        fs::write(mapping_file, mapping_tsv)
+            .with_context(|| format!("Could not write {:?}", mapping_file.as_os_str()))?;
+    }
+
+    println!(
+        "{}",
+        gfa_string(&chopped.0, SortOrder::from_matches(matches))
+    );
+
+    Ok(())
+}
@@ -0,0 +1,305 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::gfa::gfa::{entry_end, exit_end, GFAtk, SegmentEnd};
+use crate::gfa::gfa_string;
+use crate::gfa::graph::SortOrder;
+use crate::load::{load_gfa, load_gfa_stdin};
+use crate::utils::{self, parse_cigar, reverse_complement, reverse_orientation};
+use anyhow::{bail, Context, Result};
+use gfa::gfa::{Link, Orientation, Segment, GFA};
+use gfa::optfields::{OptField, OptFieldVal};
+
+/// A maximal non-branching path of oriented segments.
+#[derive(Clone, Debug)]
+pub struct Unitig {
+    /// The oriented segments, in walk order.
+    pub segments: Vec<(Vec<u8>, Orientation)>,
+    /// Whether the last segment links back to the first.
+    pub circular: bool,
+}
+
+impl Unitig {
+    /// The path notation of the unitig, e.g. `1+,2-,3+`.
+    pub fn path_string(&self) -> String {
+        self.segments
+            .iter()
+            .map(|(name, orientation)| format!("{}{}", String::from_utf8_lossy(name), orientation))
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+}
+
+/// A segment of a compacted GFA, and the unitig it was merged from.
+#[derive(Clone, Debug)]
+pub struct CompactedSegment {
+    /// The unitig of original segments.
+    pub unitig: Unitig,
+    /// The length of the merged sequence.
+    pub length: usize,
+    /// The length weighted coverage of the merged sequence.
+    pub coverage: f32,
+}
+
+/// Find the unitigs of a GFA, such that every segment is in exactly one.
+///
+/// Consecutive segments in a unitig are joined by a link which is the only link
+/// on both of the segment ends it joins (counting a link and its mirror once).
+pub fn find_unitigs(gfa: &GFAtk) -> Vec<Unitig> {
+    let adjacency = gfa.segment_end_adjacency();
+
+    // the single neighbour of a segment end, if there is one
+    let only_neighbour = |end: SegmentEnd| -> Option<SegmentEnd> {
+        match adjacency.get(&end).map(|v| v.as_slice()) {
+            Some([next]) if adjacency.get(next).is_some_and(|v| v.len() == 1) => Some(*next),
+            _ => None,
+        }
+    };
+
+    let mut visited: HashSet<&[u8]> = HashSet::new();
+    let mut unitigs = Vec::new();
+
+    for segment in &gfa.0.segments {
+        let seed = segment.name.as_slice();
+        if visited.contains(seed) {
+            continue;
+        }
+        visited.insert(seed);
+
+        // extend forward from the seed, then forward from its reverse
+        let mut circular = false;
+        let mut walks = Vec::new();
+        for start in [Orientation::Forward, Orientation::Backward] {
+            let mut walk = Vec::new();
+            let (mut name, mut orientation) = (seed, start);
+            while let Some((next, end)) = only_neighbour(exit_end(name, orientation)) {
+                // entering at the start is forward, at the end is reverse
+                let next_orientation = if end {
+                    Orientation::Backward
+                } else {
+                    Orientation::Forward
+                };
+                if next == seed {
+                    circular = next_orientation == start && start == Orientation::Forward;
+                    break;
+                }
+                if visited.contains(next) {
+                    break;
+                }
+                visited.insert(next);
+                walk.push((next.to_vec(), next_orientation));
+                (name, orientation) = (next, next_orientation);
+            }
+            walks.push(walk);
+            if circular {
+                break;
+            }
+        }
+
+        let backward = if walks.len() > 1 { walks.pop() } else { None };
+        let mut segments: Vec<(Vec<u8>, Orientation)> = backward
+            .unwrap_or_default()
+            .into_iter()
+            .rev()
+            .map(|(name, orientation)| (name, reverse_orientation(orientation)))
+            .collect();
+        segments.push((seed.to_vec(), Orientation::Forward));
+        segments.extend(walks.pop().unwrap_or_default());
+
+        unitigs.push(Unitig { segments, circular });
+    }
+
+    unitigs
+}
+
+/// Merge the maximal non-branching paths of a GFA into single segments.
+///
+/// The sequences are concatenated with the overlaps between them removed, and the
+/// coverage of each merged segment is weighted by the length each segment contributes.
+/// Links between unitigs are kept, and renamed. Returns the new GFA, and a
+/// [`CompactedSegment`] for each new segment (named from 1, in order).
+pub fn compact_gfa(gfa: &GFAtk) -> Result<(GFAtk, Vec<CompactedSegment>)> {
+    let unitigs = find_unitigs(gfa);
+
+    let sequences: HashMap<&[u8], &[u8]> = gfa
+        .0
+        .segments
+        .iter()
+        .map(|s| (s.name.as_slice(), s.sequence.as_slice()))
+        .collect();
+    let mut overlaps: HashMap<(SegmentEnd, SegmentEnd), usize> = HashMap::new();
+    for link in &gfa.0.links {
+        let from = exit_end(&link.from_segment, link.from_orient);
+        let to = entry_end(&link.to_segment, link.to_orient);
+        let overlap = parse_cigar(&link.overlap)?;
+        overlaps.insert((from, to), overlap);
+        overlaps.insert((to, from), overlap);
+    }
+
+    let mut segments = Vec::new();
+    let mut mapping = Vec::new();
+    // the segment ends at the ends of each unitig, and the new segment end
+    let mut boundaries: HashMap<(Vec<u8>, bool), (usize, bool)> = HashMap::new();
+
+    for (index, unitig) in unitigs.into_iter().enumerate() {
+        let mut sequence = Vec::new();
+        let mut weighted_coverage = 0.0;
+        let mut previous: Option<SegmentEnd> = None;
+
+        for (name, orientation) in &unitig.segments {
+            let seq = sequences
+                .get(name.as_slice())
+                .context("Segment in a unitig not found in GFA.")?;
+            let oriented = match orientation {
+                Orientation::Forward => seq.to_vec(),
+                Orientation::Backward => reverse_complement(seq),
+            };
+            let overlap = match previous {
+                Some(prev) => *overlaps
+                    .get(&(prev, entry_end(name, *orientation)))
+                    .unwrap_or(&0),
+                None => 0,
+            };
+            let contributed = &oriented[overlap.min(oriented.len())..];
+
+            let (_, coverage) = gfa.node_seq_len_and_cov(name.clone())?;
+            weighted_coverage += coverage * contributed.len() as f32;
+            sequence.extend_from_slice(contributed);
+            previous = Some(exit_end(name, *orientation));
+        }
+
+        let coverage = if sequence.is_empty() {
+            0.0
+        } else {
+            weighted_coverage / sequence.len() as f32
+        };
+
+        let (first, first_orientation) = &unitig.segments[0];
+        let (last, last_orientation) = &unitig.segments[unitig.segments.len() - 1];
+        let (_, start) = entry_end(first, *first_orientation);
+        let (_, end) = exit_end(last, *last_orientation);
+        boundaries.insert((first.clone(), start), (index, false));
+        boundaries.insert((last.clone(), end), (index, true));
+
+        let name = (index + 1).to_string().into_bytes();
+        segments.push(Segment {
+            name,
+            sequence: sequence.clone(),
+            optional: vec![OptField::new(b"ll", OptFieldVal::Float(coverage))],
+        });
+        mapping.push(CompactedSegment {
+            unitig,
+            length: sequence.len(),
+            coverage,
+        });
+    }
+
+    // keep the links joining unitig ends, the rest are internal to a unitig
+    let mut links = Vec::new();
+    for link in &gfa.0.links {
+        let (_, from_end) = exit_end(&link.from_segment, link.from_orient);
+        let (_, to_end) = entry_end(&link.to_segment, link.to_orient);
+        let from = boundaries.get(&(link.from_segment.clone(), from_end));
+        let to = boundaries.get(&(link.to_segment.clone(), to_end));
+        if let (Some((from, from_end)), Some((to, to_end))) = (from, to) {
+            links.push(Link {
+                from_segment: (from + 1).to_string().into_bytes(),
+                from_orient: if *from_end {
+                    Orientation::Forward
+                } else {
+                    Orientation::Backward
+                },
+                to_segment: (to + 1).to_string().into_bytes(),
+                to_orient: if *to_end {
+                    Orientation::Backward
+                } else {
+                    Orientation::Forward
+                },
+                overlap: link.overlap.clone(),
+                optional: link.optional.clone(),
+            });
+        }
+    }
+
+    Ok((
+        GFAtk(GFA {
+            header: gfa.0.header.clone(),
+            segments,
+            links,
+            paths: Vec::new(),
+            containments: Vec::new(),
+        }),
+        mapping,
+    ))
+}
+
+/// Compact a GFA by merging maximal non-branching paths (unitigs) into single segments.
+///
+/// The compacted GFA is printed to STDOUT, and optionally a TSV mapping each new
+/// segment to the original oriented segments is written to `--mapping`.
+///
+/// For example:
+/// ```bash
+/// gfatk compact in.gfa -m mapping.tsv > out.gfa
+/// ```
+pub fn compact(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let mapping_file = matches.get_one::<PathBuf>("mapping");
+
+    let gfa: GFAtk = match gfa_file {
+        Some(f) => {
+            let ext = f.extension();
+            match ext {
+                Some(e) => {
+                    if e == "gfa" {
+                        GFAtk(load_gfa(f)?)
+                    } else {
+                        bail!("Input is not a GFA.")
+                    }
+                }
+                None => bail!("Could not read file."),
+            }
+        }
+        None => match utils::is_stdin() {
+            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            false => bail!("No input from STDIN. Run `gfatk compact -h` for help."),
+        },
+    };
+
+    if !gfa.0.paths.is_empty() || !gfa.0.containments.is_empty() {
+        eprintln!("[-]\tPath (P) and containment (C) lines are not carried over.");
+    }
+
+    let (compacted, mapping) = compact_gfa(&gfa)?;
+
+    eprintln!(
+        "[+]\tCompacted {} segments into {} segments.",
+        gfa.0.segments.len(),
+        compacted.0.segments.len()
+    );
+
+    if let Some(mapping_file) = mapping_file {
+        let mut mapping_tsv =
+            String::from("segment\tlength\tcoverage\tcircular\toriginal_segments\n");
+        for (index, segment) in mapping.iter().enumerate() {
+            mapping_tsv += &format!(
+                "{}\t{}\t{}\t{}\t{}\n",
+                index + 1,
+                segment.length,
+                segment.coverage,
+                segment.unitig.circular,
+                segment.unitig.path_string()
+            );
+        }
+        fs::write(mapping_file, mapping_tsv)
+            .with_context(|| format!("Could not write {:?}", mapping_file.as_os_str()))?;
+    }
+
+    println!(
+        "{}",
+        gfa_string(&compacted.0, SortOrder::from_matches(matches))
+    );
+
+    Ok(())
+}
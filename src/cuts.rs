@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+
+use crate::gfa::gfa::GFAtk;
+use crate::gfa::graph::CutElement;
+use crate::load::{load_gfa, load_gfa_stdin};
+use crate::utils::{self, get_edge_coverage};
+use anyhow::{bail, Result};
+
+/// List the bridges and articulation points of each component of a GFA.
+///
+/// A bridge is a link, and an articulation point a segment, whose removal splits
+/// its component in pieces. These are where the graph is fragile, and where chimeric
+/// joins (e.g. between an organelle and the nuclear genome) are often found.
+///
+/// Prints a TSV of each cut, with the length (the overlap, for a link) and
+/// coverage of the cut, and the number of segments and total length of each piece.
+///
+/// For example:
+/// ```bash
+/// gfatk cuts in.gfa > cuts.tsv
+/// ```
+pub fn cuts(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_file = matches.get_one::<PathBuf>("GFA");
+
+    let gfa: GFAtk = match gfa_file {
+        Some(f) => {
+            let ext = f.extension();
+            match ext {
+                Some(e) => {
+                    if e == "gfa" {
+                        GFAtk(load_gfa(f)?)
+                    } else {
+                        bail!("Input is not a GFA.")
+                    }
+                }
+                None => bail!("Could not read file."),
+            }
+        }
+        None => match utils::is_stdin() {
+            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            false => bail!("No input from STDIN. Run `gfatk cuts -h` for help."),
+        },
+    };
+
+    let gfa = gfa.sorted(matches);
+
+    let (graph_indices, gfa_graph) = gfa.into_digraph()?;
+    let overlaps = gfa.link_overlaps()?;
+
+    let mut lengths = Vec::new();
+    let mut coverages = Vec::new();
+    for node in gfa_graph.0.node_indices() {
+        let (length, coverage) = gfa.node_seq_len_and_cov(gfa_graph.0[node].clone())?;
+        lengths.push(length);
+        coverages.push(coverage);
+    }
+
+    let components = gfa_graph.weakly_connected_components(graph_indices)?;
+    let mut component_of = std::collections::HashMap::new();
+    for (index, component) in components.iter().enumerate() {
+        for segment in component {
+            component_of.insert(segment.as_slice(), index);
+        }
+    }
+
+    let cuts = gfa_graph.cuts(&lengths);
+
+    let bridges = cuts
+        .iter()
+        .filter(|c| matches!(c.element, CutElement::Bridge(_)))
+        .count();
+    eprintln!(
+        "[+]\tFound {} bridge(s) and {} articulation point(s).",
+        bridges,
+        cuts.len() - bridges
+    );
+
+    println!("component\ttype\telement\tlength\tcoverage\tpieces\tpiece_segments\tpiece_lengths");
+    for cut in cuts {
+        let (kind, segment, element, length, coverage) = match cut.element {
+            CutElement::Bridge(edge) => {
+                let link = &gfa.0.links[edge.index()];
+                (
+                    "bridge",
+                    &link.from_segment,
+                    format!(
+                        "{}{},{}{}",
+                        String::from_utf8_lossy(&link.from_segment),
+                        link.from_orient,
+                        String::from_utf8_lossy(&link.to_segment),
+                        link.to_orient
+                    ),
+                    overlaps[edge.index()],
+                    get_edge_coverage(&link.optional)? as f32,
+                )
+            }
+            CutElement::Articulation(node) => {
+                let name = &gfa_graph.0[node];
+                (
+                    "articulation",
+                    name,
+                    String::from_utf8_lossy(name).to_string(),
+                    lengths[node.index()],
+                    coverages[node.index()],
+                )
+            }
+        };
+        let join = |f: fn(&(usize, usize)) -> usize| {
+            cut.pieces
+                .iter()
+                .map(|p| f(p).to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            component_of[segment.as_slice()],
+            kind,
+            element,
+            length,
+            coverage,
+            cut.pieces.len(),
+            join(|p| p.0),
+            join(|p| p.1)
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::gfa::gfa::GFAtk;
+use crate::gfa::graph::Cycle;
+use crate::load::{load_gfa, load_gfa_stdin};
+use crate::utils;
+use anyhow::{bail, Result};
+
+/// List the simple cycles of a GFA: candidate circular molecules.
+///
+/// A simple cycle is a closed, orientation-consistent walk through each of its
+/// segments once, and a cycle and its reverse complement are reported once. The
+/// search stops at `--max-cycles`, and skips cycles longer than `--max-length`.
+///
+/// Prints a TSV of each cycle, longest first within each component, with its length
+/// (overlaps removed), minimum link coverage and mean segment coverage. The cycles
+/// are in the notation of `gfatk path`.
+///
+/// For example:
+/// ```bash
+/// gfatk cycles in.gfa --max-length 500000 > cycles.tsv
+/// ```
+pub fn cycles(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let max_cycles = *matches
+        .get_one::<usize>("max-cycles")
+        .expect("defaulted by clap");
+    let max_length = matches.get_one::<usize>("max-length").copied();
+
+    let gfa: GFAtk = match gfa_file {
+        Some(f) => {
+            let ext = f.extension();
+            match ext {
+                Some(e) => {
+                    if e == "gfa" {
+                        GFAtk(load_gfa(f)?)
+                    } else {
+                        bail!("Input is not a GFA.")
+                    }
+                }
+                None => bail!("Could not read file."),
+            }
+        }
+        None => match utils::is_stdin() {
+            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            false => bail!("No input from STDIN. Run `gfatk cycles -h` for help."),
+        },
+    };
+
+    let gfa = gfa.sorted(matches);
+
+    let (graph_indices, gfa_graph) = gfa.into_digraph()?;
+    let overlaps = gfa.link_overlaps()?;
+
+    let mut lengths = Vec::new();
+    let mut coverages = Vec::new();
+    for node in gfa_graph.0.node_indices() {
+        let (length, coverage) = gfa.node_seq_len_and_cov(gfa_graph.0[node].clone())?;
+        lengths.push(length);
+        coverages.push(coverage);
+    }
+
+    let components = gfa_graph.weakly_connected_components(graph_indices)?;
+    let mut component_of = HashMap::new();
+    for (index, component) in components.iter().enumerate() {
+        for segment in component {
+            component_of.insert(segment.as_slice(), index);
+        }
+    }
+
+    let mut cycles = gfa_graph.simple_cycles(&lengths, &overlaps, max_cycles, max_length);
+    if cycles.len() >= max_cycles {
+        eprintln!(
+            "[-]\tStopped at {} cycles. Increase --max-cycles to find more.",
+            max_cycles
+        );
+    }
+    eprintln!("[+]\tFound {} cycle(s).", cycles.len());
+
+    let component = |cycle: &Cycle| component_of[gfa_graph.0[cycle.steps[0].0].as_slice()];
+    cycles.sort_by_key(|c| (component(c), std::cmp::Reverse(c.length)));
+
+    println!("component\tlength\tsegments\tmin_link_coverage\tmean_coverage\tpath");
+    for cycle in &cycles {
+        let path = cycle
+            .steps
+            .iter()
+            .map(|(node, orientation)| {
+                format!(
+                    "{}{}",
+                    String::from_utf8_lossy(&gfa_graph.0[*node]),
+                    orientation
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+        let min_link_coverage = cycle
+            .edges
+            .iter()
+            .filter_map(|edge| gfa_graph.0[*edge].2)
+            .min()
+            .unwrap_or(0);
+        let mean_coverage = cycle
+            .steps
+            .iter()
+            .map(|(node, _)| coverages[node.index()])
+            .sum::<f32>()
+            / cycle.steps.len() as f32;
+        println!(
+            "{}\t{}\t{}\t{}\t{:.2}\t{}",
+            component(cycle),
+            cycle.length,
+            cycle.steps.len(),
+            min_link_coverage,
+            mean_coverage,
+            path
+        );
+    }
+
+    Ok(())
+}
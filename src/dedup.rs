@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::gfa::gfa::GFAtk;
+use crate::gfa::gfa_string;
+use crate::gfa::graph::SortOrder;
+use crate::load::{load_gfa, load_gfa_stdin};
+use crate::utils::{self, get_edge_coverage, natural_cmp, reverse_complement, reverse_orientation};
+use anyhow::{bail, Context, Result};
+use gfa::gfa::{Link, Orientation, Path, Segment};
+use gfa::optfields::{OptField, OptFieldVal, OptionalFields};
+
+/// An adjacency, keyed by the smaller of a link and its mirror, with orientations as
+/// whether they are reversed.
+type LinkKey = (Vec<u8>, bool, Vec<u8>, bool);
+
+/// The adjacency of a link from `from` to `to`.
+fn link_key(from: &[u8], from_orient: Orientation, to: &[u8], to_orient: Orientation) -> LinkKey {
+    let forward = (
+        from.to_vec(),
+        from_orient.is_reverse(),
+        to.to_vec(),
+        to_orient.is_reverse(),
+    );
+    let mirror = (
+        to.to_vec(),
+        !to_orient.is_reverse(),
+        from.to_vec(),
+        !from_orient.is_reverse(),
+    );
+    forward.min(mirror)
+}
+
+/// Segments with identical, or reverse complement identical, sequences.
+#[derive(Clone, Debug)]
+pub struct DuplicateGroup {
+    /// The segment the group collapses into, the first by name.
+    pub keep: Vec<u8>,
+    /// Every segment of the group (including `keep`), and whether its sequence is
+    /// the reverse complement of the sequence of `keep`.
+    pub segments: Vec<(Vec<u8>, bool)>,
+}
+
+/// Find the groups of segments in a GFA with identical sequences, or (with
+/// `reverse_complements`) reverse complement identical sequences.
+///
+/// Groups are in the order of their first segment in the GFA. Segments without a
+/// sequence (`*`) are never duplicates.
+pub fn find_duplicates(gfa: &GFAtk, reverse_complements: bool) -> Vec<DuplicateGroup> {
+    let mut order = Vec::new();
+    let mut groups: HashMap<Vec<u8>, Vec<&Segment<Vec<u8>, _>>> = HashMap::new();
+    for segment in &gfa.0.segments {
+        if segment.sequence.is_empty() || segment.sequence == b"*" {
+            continue;
+        }
+        let key = match reverse_complements {
+            true => segment
+                .sequence
+                .clone()
+                .min(reverse_complement(&segment.sequence)),
+            false => segment.sequence.clone(),
+        };
+        let group = groups.entry(key.clone()).or_default();
+        if group.is_empty() {
+            order.push(key);
+        }
+        group.push(segment);
+    }
+
+    order
+        .iter()
+        .filter(|key| groups[*key].len() > 1)
+        .map(|key| {
+            let mut segments = groups[key].clone();
+            segments.sort_by(|a, b| natural_cmp(&a.name, &b.name));
+            let keep = segments[0];
+            DuplicateGroup {
+                keep: keep.name.clone(),
+                segments: segments
+                    .iter()
+                    .map(|s| (s.name.clone(), s.sequence != keep.sequence))
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Collapse each group of duplicate segments into its first segment.
+///
+/// The other segments are removed, and their links, containments and path steps
+/// moved to the kept segment (reversed, if it was the reverse complement), and the
+/// coverage (`ll`) of the kept segment is the sum over its group. Links which are
+/// then the same adjacency (identical, or each other's mirror) are written once,
+/// with their edge coverages (`ec`) summed; a link and its mirror in the input count
+/// once. If the input has mirrored links, the mirrors are written again. Containments
+/// of a segment in itself are dropped.
+pub fn collapse_duplicates(gfa: &GFAtk, groups: &[DuplicateGroup]) -> Result<GFAtk> {
+    let mut new_names: HashMap<&[u8], (&[u8], bool)> = HashMap::new();
+    for group in groups {
+        for (name, reversed) in &group.segments {
+            new_names.insert(name, (&group.keep, *reversed));
+        }
+    }
+    let lookup = |name: &[u8], orientation: Orientation| match new_names.get(name) {
+        Some((keep, true)) => (keep.to_vec(), reverse_orientation(orientation)),
+        Some((keep, false)) => (keep.to_vec(), orientation),
+        None => (name.to_vec(), orientation),
+    };
+
+    // the coverage of each group, summed over its segments
+    let mut coverages: HashMap<&[u8], f32> = HashMap::new();
+    for segment in &gfa.0.segments {
+        if let Some((keep, _)) = new_names.get(segment.name.as_slice()) {
+            for opt in segment.optional.iter() {
+                if let (b"ll", OptFieldVal::Float(f)) = (&opt.tag, &opt.value) {
+                    *coverages.entry(keep).or_default() += f;
+                }
+            }
+        }
+    }
+
+    let mut collapsed = gfa.0.clone();
+    collapsed.segments.retain(|s| {
+        new_names
+            .get(s.name.as_slice())
+            .is_none_or(|(keep, _)| *keep == s.name)
+    });
+    for segment in collapsed.segments.iter_mut() {
+        let Some(coverage) = coverages.get(segment.name.as_slice()) else {
+            continue;
+        };
+        for opt in segment.optional.iter_mut() {
+            if opt.tag == *b"ll" {
+                *opt = OptField::new(b"ll", OptFieldVal::Float(*coverage));
+            }
+        }
+    }
+
+    // the first link of each adjacency once rewired, and the highest edge coverage
+    // of each adjacency of the input merged into it
+    type Merged = (Link<Vec<u8>, OptionalFields>, HashMap<LinkKey, i64>);
+    let mut order = Vec::new();
+    let mut merged: HashMap<LinkKey, Merged> = HashMap::new();
+    for link in &gfa.0.links {
+        let (from, from_orient) = lookup(&link.from_segment, link.from_orient);
+        let (to, to_orient) = lookup(&link.to_segment, link.to_orient);
+        let key = link_key(&from, from_orient, &to, to_orient);
+        let (_, edge_coverages) = merged.entry(key.clone()).or_insert_with(|| {
+            order.push(key);
+            let mut link = link.clone();
+            (link.from_segment, link.from_orient) = (from.clone(), from_orient);
+            (link.to_segment, link.to_orient) = (to.clone(), to_orient);
+            (link, HashMap::new())
+        });
+        if let Ok(ec) = get_edge_coverage(&link.optional) {
+            let original = link_key(
+                &link.from_segment,
+                link.from_orient,
+                &link.to_segment,
+                link.to_orient,
+            );
+            let coverage = edge_coverages.entry(original).or_insert(ec);
+            *coverage = (*coverage).max(ec);
+        }
+    }
+    collapsed.links = order
+        .iter()
+        .map(|key| {
+            let (mut link, edge_coverages) = merged.remove(key).expect("added with its key");
+            if !edge_coverages.is_empty() {
+                let ec = edge_coverages.values().sum();
+                for field in link.optional.iter_mut() {
+                    if matches!(&field.tag, b"ec" | b"EC") {
+                        field.value = OptFieldVal::Int(ec);
+                    }
+                }
+            }
+            link
+        })
+        .collect();
+
+    collapsed.containments = Vec::new();
+    for containment in &gfa.0.containments {
+        let mut containment = containment.clone();
+        (containment.container_name, containment.container_orient) =
+            lookup(&containment.container_name, containment.container_orient);
+        (containment.contained_name, containment.contained_orient) =
+            lookup(&containment.contained_name, containment.contained_orient);
+        if containment.container_name != containment.contained_name {
+            collapsed.containments.push(containment);
+        }
+    }
+
+    collapsed.paths = gfa
+        .0
+        .paths
+        .iter()
+        .map(|p| {
+            let steps: Vec<String> = p
+                .iter()
+                .map(|(name, orientation)| {
+                    let (name, orientation) = lookup(name.as_ref(), orientation);
+                    format!("{}{}", String::from_utf8_lossy(&name), orientation)
+                })
+                .collect();
+            Path::new(
+                p.path_name.clone(),
+                steps.join(",").into_bytes(),
+                p.overlaps.clone(),
+                p.optional.clone(),
+            )
+        })
+        .collect();
+
+    match gfa.normalize_links(false)?.1.mirror_pairs > 0 {
+        true => Ok(GFAtk(collapsed).normalize_links(true)?.0),
+        false => Ok(GFAtk(collapsed)),
+    }
+}
+
+/// Report segments of a GFA with identical, or reverse complement identical,
+/// sequences under different names, e.g. in merged assemblies.
+///
+/// Prints a TSV with a row for each segment of each group, and its orientation
+/// relative to the segment kept. Optionally collapses each group into its first
+/// segment (by name), writing the GFA to `--collapse`.
+///
+/// For example:
+/// ```bash
+/// gfatk dedup in.gfa --collapse dedup.gfa > duplicates.tsv
+/// ```
+pub fn dedup(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let collapse_file = matches.get_one::<PathBuf>("collapse");
+    let same_strand = matches.get_flag("same-strand");
+
+    let gfa: GFAtk = match gfa_file {
+        Some(f) => {
+            let ext = f.extension();
+            match ext {
+                Some(e) => {
+                    if e == "gfa" {
+                        GFAtk(load_gfa(f)?)
+                    } else {
+                        bail!("Input is not a GFA.")
+                    }
+                }
+                None => bail!("Could not read file."),
+            }
+        }
+        None => match utils::is_stdin() {
+            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            false => bail!("No input from STDIN. Run `gfatk dedup -h` for help."),
+        },
+    };
+
+    let gfa = gfa.sorted(matches);
+
+    let groups = find_duplicates(&gfa, !same_strand);
+    let duplicates: usize = groups.iter().map(|g| g.segments.len() - 1).sum();
+    eprintln!(
+        "[+]\tFound {} group(s) of duplicate segments, with {} duplicate(s).",
+        groups.len(),
+        duplicates
+    );
+
+    let lengths: HashMap<&[u8], usize> = gfa
+        .0
+        .segments
+        .iter()
+        .map(|s| (s.name.as_slice(), s.sequence.len()))
+        .collect();
+
+    println!("group\tsegment\tkept\torientation\tlength");
+    for (index, group) in groups.iter().enumerate() {
+        for (name, reversed) in &group.segments {
+            println!(
+                "{}\t{}\t{}\t{}\t{}",
+                index,
+                String::from_utf8_lossy(name),
+                String::from_utf8_lossy(&group.keep),
+                if *reversed { "-" } else { "+" },
+                lengths[name.as_slice()]
+            );
+        }
+    }
+
+    if let Some(collapse_file) = collapse_file {
+        let collapsed = collapse_duplicates(&gfa, &groups)?;
+        fs::write(
+            collapse_file,
+            gfa_string(&collapsed.0, SortOrder::from_matches(matches)),
+        )
+        .with_context(|| format!("Could not write {:?}", collapse_file.as_os_str()))?;
+        eprintln!(
+            "[+]\tRemoved {} duplicate segment(s), written to {:?}",
+            duplicates,
+            collapse_file.as_os_str()
+        );
+    }
+
+    Ok(())
+}
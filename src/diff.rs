@@ -0,0 +1,393 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::gfa::gfa::GFAtk;
+use crate::load::load_gfa;
+use crate::utils::{get_edge_coverage, reverse_complement, reverse_orientation};
+use anyhow::{bail, Result};
+use gfa::gfa::Orientation;
+
+/// A single difference between two GFAs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Difference {
+    /// The kind of change, e.g. `segment_added` or `link_removed`.
+    pub change: &'static str,
+    /// The segment, link or component changed, named as in the GFA it is in.
+    pub element: String,
+    /// The value in the first GFA, if any.
+    pub a: String,
+    /// The value in the second GFA, if any.
+    pub b: String,
+}
+
+/// A segment of the second GFA, named as in the first if it is matched there.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Node {
+    /// A segment in the first GFA.
+    A(Vec<u8>),
+    /// A segment only in the second GFA.
+    B(Vec<u8>),
+}
+
+/// A changed sequence, by its length and its base at the first position (1-based)
+/// where it differs from the `other`, e.g. `4bp, C at 4`, or `3bp, end at 4` if the
+/// other is longer.
+fn sequence_change(sequence: &[u8], other: &[u8]) -> String {
+    let position = sequence
+        .iter()
+        .zip(other)
+        .position(|(a, b)| a != b)
+        .unwrap_or(sequence.len().min(other.len()));
+    let base = match sequence.get(position) {
+        Some(base) => (*base as char).to_string(),
+        None => "end".to_string(),
+    };
+    format!("{}bp, {} at {}", sequence.len(), base, position + 1)
+}
+
+/// A link between oriented [`Node`]s.
+type LinkKey = (Node, Orientation, Node, Orientation);
+
+/// A link and its mirror are one adjacency, keyed by the smaller.
+fn canonical_link(
+    from: Node,
+    from_orient: Orientation,
+    to: Node,
+    to_orient: Orientation,
+) -> LinkKey {
+    let forward = (from.clone(), from_orient, to.clone(), to_orient);
+    let mirror = (
+        to,
+        reverse_orientation(to_orient),
+        from,
+        reverse_orientation(from_orient),
+    );
+    if (
+        &mirror.0,
+        mirror.1.is_reverse(),
+        &mirror.2,
+        mirror.3.is_reverse(),
+    ) < (
+        &forward.0,
+        forward.1.is_reverse(),
+        &forward.2,
+        forward.3.is_reverse(),
+    ) {
+        mirror
+    } else {
+        forward
+    }
+}
+
+/// Whether two coverages differ by more than `threshold`, as a fraction of the first.
+fn coverage_changed(a: f32, b: f32, threshold: f32) -> bool {
+    (b - a).abs() > threshold * a.abs()
+}
+
+/// Compare two GFAs.
+///
+/// Segments are matched by name, and then the remaining segments by sequence (or
+/// reverse complement), which are reported as renamed. Reports segments added and
+/// removed, sequences changed, segments renamed, links gained and lost (counting a
+/// link and its mirror once), segment and link coverages changed by more than
+/// `coverage_threshold` (a fraction of the first), and weakly connected components
+/// which are split, merged, added or removed.
+pub fn diff_gfas(a: &GFAtk, b: &GFAtk, coverage_threshold: f32) -> Result<Vec<Difference>> {
+    let mut differences = Vec::new();
+    let mut difference = |change, element: String, a: String, b: String| {
+        differences.push(Difference {
+            change,
+            element,
+            a,
+            b,
+        })
+    };
+    let lossy = |name: &[u8]| String::from_utf8_lossy(name).to_string();
+
+    // the node and orientation of each segment of b
+    let mut b_nodes: HashMap<&[u8], (Node, Orientation)> = HashMap::new();
+    let a_names: HashMap<&[u8], &[u8]> =
+        a.0.segments
+            .iter()
+            .map(|s| (s.name.as_slice(), s.sequence.as_slice()))
+            .collect();
+
+    // by name
+    for segment in &b.0.segments {
+        if let Some(sequence) = a_names.get(segment.name.as_slice()) {
+            if *sequence != segment.sequence.as_slice() {
+                difference(
+                    "sequence_changed",
+                    lossy(&segment.name),
+                    sequence_change(sequence, &segment.sequence),
+                    sequence_change(&segment.sequence, sequence),
+                );
+            }
+            b_nodes.insert(
+                &segment.name,
+                (Node::A(segment.name.clone()), Orientation::Forward),
+            );
+        }
+    }
+
+    // then the rest by sequence, or reverse complement
+    let b_names: HashSet<&[u8]> = b.0.segments.iter().map(|s| s.name.as_slice()).collect();
+    let mut sequences: HashMap<Vec<u8>, (&[u8], Orientation)> = HashMap::new();
+    for segment in &a.0.segments {
+        if b_names.contains(segment.name.as_slice()) {
+            continue;
+        }
+        sequences
+            .entry(reverse_complement(&segment.sequence))
+            .or_insert((&segment.name, Orientation::Backward));
+        sequences
+            .entry(segment.sequence.clone())
+            .insert_entry((&segment.name, Orientation::Forward));
+    }
+    let mut renamed: HashSet<&[u8]> = HashSet::new();
+    for segment in &b.0.segments {
+        if b_nodes.contains_key(segment.name.as_slice()) {
+            continue;
+        }
+        let (node, orientation) = match sequences.get(&segment.sequence) {
+            Some((name, orientation)) if renamed.insert(name) => {
+                difference(
+                    "segment_renamed",
+                    lossy(&segment.name),
+                    format!("{}{}", lossy(name), orientation),
+                    format!("{}+", lossy(&segment.name)),
+                );
+                (Node::A(name.to_vec()), *orientation)
+            }
+            _ => (Node::B(segment.name.clone()), Orientation::Forward),
+        };
+        b_nodes.insert(&segment.name, (node, orientation));
+    }
+
+    // segments
+    let matched: BTreeSet<&Node> = b_nodes.values().map(|(n, _)| n).collect();
+    for segment in &a.0.segments {
+        if !matched.contains(&Node::A(segment.name.clone())) {
+            difference(
+                "segment_removed",
+                lossy(&segment.name),
+                format!("{}bp", segment.sequence.len()),
+                String::new(),
+            );
+        }
+    }
+    for segment in &b.0.segments {
+        match &b_nodes[segment.name.as_slice()].0 {
+            Node::B(_) => difference(
+                "segment_added",
+                lossy(&segment.name),
+                String::new(),
+                format!("{}bp", segment.sequence.len()),
+            ),
+            Node::A(name) => {
+                let a_coverage = a.node_seq_len_and_cov(name.clone()).ok();
+                let b_coverage = b.node_seq_len_and_cov(segment.name.clone()).ok();
+                if let (Some((_, ca)), Some((_, cb))) = (a_coverage, b_coverage) {
+                    if coverage_changed(ca, cb, coverage_threshold) {
+                        difference(
+                            "coverage_changed",
+                            lossy(name),
+                            ca.to_string(),
+                            cb.to_string(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // links, with their edge coverage and how they are written
+    let mut a_links: HashMap<LinkKey, (Option<i64>, String)> = HashMap::new();
+    for link in a.unique_links() {
+        let key = canonical_link(
+            Node::A(link.from_segment.clone()),
+            link.from_orient,
+            Node::A(link.to_segment.clone()),
+            link.to_orient,
+        );
+        let name = format!(
+            "{}{},{}{}",
+            lossy(&link.from_segment),
+            link.from_orient,
+            lossy(&link.to_segment),
+            link.to_orient
+        );
+        a_links.insert(key, (get_edge_coverage(&link.optional).ok(), name));
+    }
+    let mut b_links: HashMap<LinkKey, (Option<i64>, String)> = HashMap::new();
+    for link in b.unique_links() {
+        let node = |name: &[u8], orientation: Orientation| -> Result<(Node, Orientation)> {
+            match b_nodes.get(name) {
+                Some((node, Orientation::Forward)) => Ok((node.clone(), orientation)),
+                Some((node, Orientation::Backward)) => {
+                    Ok((node.clone(), reverse_orientation(orientation)))
+                }
+                None => bail!("Segment {} not found in GFA.", lossy(name)),
+            }
+        };
+        let (from, from_orient) = node(&link.from_segment, link.from_orient)?;
+        let (to, to_orient) = node(&link.to_segment, link.to_orient)?;
+        let name = format!(
+            "{}{},{}{}",
+            lossy(&link.from_segment),
+            link.from_orient,
+            lossy(&link.to_segment),
+            link.to_orient
+        );
+        b_links.insert(
+            canonical_link(from, from_orient, to, to_orient),
+            (get_edge_coverage(&link.optional).ok(), name),
+        );
+    }
+
+    let mut link_keys: Vec<&LinkKey> = a_links.keys().chain(b_links.keys()).collect();
+    link_keys.sort_by(|x, y| {
+        (&x.0, x.1.is_reverse(), &x.2, x.3.is_reverse()).cmp(&(
+            &y.0,
+            y.1.is_reverse(),
+            &y.2,
+            y.3.is_reverse(),
+        ))
+    });
+    link_keys.dedup();
+    for key in link_keys {
+        match (a_links.get(key), b_links.get(key)) {
+            (Some((_, name)), None) => {
+                difference("link_removed", name.clone(), name.clone(), String::new())
+            }
+            (None, Some((_, name))) => {
+                difference("link_added", name.clone(), String::new(), name.clone())
+            }
+            (Some((Some(ca), name)), Some((Some(cb), _)))
+                if coverage_changed(*ca as f32, *cb as f32, coverage_threshold) =>
+            {
+                difference(
+                    "link_coverage_changed",
+                    name.clone(),
+                    ca.to_string(),
+                    cb.to_string(),
+                )
+            }
+            _ => (),
+        }
+    }
+
+    // components
+    let components = |gfa: &GFAtk| -> Result<Vec<Vec<Vec<u8>>>> {
+        let (graph_indices, gfa_graph) = gfa.into_digraph()?;
+        gfa_graph.weakly_connected_components(graph_indices)
+    };
+    let a_components = components(a)?;
+    let b_components = components(b)?;
+
+    let mut a_component_of: HashMap<Node, usize> = HashMap::new();
+    for (index, component) in a_components.iter().enumerate() {
+        for segment in component {
+            a_component_of.insert(Node::A(segment.clone()), index);
+        }
+    }
+    let mut b_component_of: HashMap<Node, usize> = HashMap::new();
+    for (index, component) in b_components.iter().enumerate() {
+        for segment in component {
+            b_component_of.insert(b_nodes[segment.as_slice()].0.clone(), index);
+        }
+    }
+    let join = |indices: &BTreeSet<usize>| {
+        indices
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    };
+
+    for (index, component) in a_components.iter().enumerate() {
+        let in_b: BTreeSet<usize> = component
+            .iter()
+            .filter_map(|s| b_component_of.get(&Node::A(s.clone())).copied())
+            .collect();
+        match in_b.len() {
+            0 => difference(
+                "component_removed",
+                index.to_string(),
+                format!("{} segments", component.len()),
+                String::new(),
+            ),
+            1 => (),
+            _ => difference(
+                "component_split",
+                index.to_string(),
+                index.to_string(),
+                join(&in_b),
+            ),
+        }
+    }
+    for (index, component) in b_components.iter().enumerate() {
+        let in_a: BTreeSet<usize> = component
+            .iter()
+            .filter_map(|s| a_component_of.get(&b_nodes[s.as_slice()].0).copied())
+            .collect();
+        match in_a.len() {
+            0 => difference(
+                "component_added",
+                index.to_string(),
+                String::new(),
+                format!("{} segments", component.len()),
+            ),
+            1 => (),
+            _ => difference(
+                "component_merged",
+                index.to_string(),
+                join(&in_a),
+                index.to_string(),
+            ),
+        }
+    }
+
+    Ok(differences)
+}
+
+/// Report the differences between two GFAs.
+///
+/// Prints a TSV of each change, with the values in the first and second GFA.
+/// Components are indexed as in `gfatk stats`, in their own GFA.
+///
+/// For example:
+/// ```bash
+/// gfatk diff in.gfa trimmed.gfa > diff.tsv
+/// ```
+pub fn diff(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_a = matches
+        .get_one::<PathBuf>("GFA-A")
+        .expect("required by clap");
+    let gfa_b = matches
+        .get_one::<PathBuf>("GFA-B")
+        .expect("required by clap");
+    let coverage_threshold = *matches
+        .get_one::<f32>("coverage-threshold")
+        .expect("defaulted by clap");
+
+    let load = |f: &PathBuf| -> Result<GFAtk> {
+        match f.extension() {
+            Some(e) if e == "gfa" => Ok(GFAtk(load_gfa(f)?)),
+            Some(_) => bail!("Input {:?} is not a GFA.", f.as_os_str()),
+            None => bail!("Could not read file {:?}.", f.as_os_str()),
+        }
+    };
+    let (a, b) = (load(gfa_a)?.sorted(matches), load(gfa_b)?.sorted(matches));
+
+    let differences = diff_gfas(&a, &b, coverage_threshold)?;
+
+    eprintln!("[+]\tFound {} difference(s).", differences.len());
+
+    println!("change\telement\ta\tb");
+    for d in differences {
+        println!("{}\t{}\t{}\t{}", d.change, d.element, d.a, d.b);
+    }
+
+    Ok(())
+}
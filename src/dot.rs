@@ -16,6 +16,7 @@ use anyhow::{bail, Result};
 /// ```
 pub fn dot(matches: &clap::ArgMatches) -> Result<()> {
     let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let normalize_links = matches.get_flag("normalize-links");
 
     let gfa: GFAtk = match gfa_file {
         Some(f) => {
@@ -23,7 +24,7 @@ pub fn dot(matches: &clap::ArgMatches) -> Result<()> {
             match ext {
                 Some(e) => {
                     if e == "gfa" {
-                        GFAtk(load_gfa(f)?)
+                        GFAtk::new(load_gfa(f)?, normalize_links)?
                     } else {
                         bail!("Input is not a GFA.")
                     }
@@ -32,7 +33,7 @@ pub fn dot(matches: &clap::ArgMatches) -> Result<()> {
             }
         }
         None => match utils::is_stdin() {
-            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            true => GFAtk::new(load_gfa_stdin(std::io::stdin().lock())?, normalize_links)?,
             false => bail!("No input from STDIN. Run `gfatk dot -h` for help."),
         },
     };
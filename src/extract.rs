@@ -1,6 +1,8 @@
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 use crate::gfa::gfa::GFAtk;
+use crate::gfa::graph::SortOrder;
 use crate::load::{load_gfa, load_gfa_stdin};
 use crate::utils;
 use anyhow::{bail, Context, Result};
@@ -8,9 +10,15 @@ use petgraph::graph::NodeIndex;
 
 /// Supply a sequence/segment ID from the GFA, and extract the GFA with all nodes connected to the input node.
 ///
+/// If a distance is supplied, the subgraph is instead grown by sequence distance, following
+/// segment orientations, optionally stopping at given segments or at segments outside a
+/// coverage range.
+///
 /// For example:
 /// ```bash
 /// gfatk extract in.gfa -s 1 > out.gfa
+/// # everything starting within 10kb of segment 1
+/// gfatk extract in.gfa -s 1 -d 10000 --min-coverage 20 > out.gfa
 /// ```
 pub fn extract(matches: &clap::ArgMatches) -> Result<()> {
     // read in path and parse gfa
@@ -22,6 +30,13 @@ pub fn extract(matches: &clap::ArgMatches) -> Result<()> {
     let iterations = *matches
         .get_one::<i32>("iterations")
         .expect("defaulted by clap");
+    let distance = matches.get_one::<usize>("distance");
+    let stop_segments = matches
+        .get_many::<String>("stop-segments")
+        .map(|e| e.collect::<Vec<_>>())
+        .unwrap_or_default();
+    let min_coverage = matches.get_one::<f32>("min-coverage");
+    let max_coverage = matches.get_one::<f32>("max-coverage");
 
     let gfa: GFAtk = match gfa_file {
         Some(f) => {
@@ -43,6 +58,50 @@ pub fn extract(matches: &clap::ArgMatches) -> Result<()> {
         },
     };
 
+    if let Some(distance) = distance {
+        let (graph_indices, gfa_graph) = gfa.into_digraph()?;
+
+        let target_indices = sequence_ids
+            .iter()
+            .map(|e| graph_indices.seg_id_to_node_index(e.as_bytes().to_vec()))
+            .collect::<Result<Vec<NodeIndex>>>()
+            .context("One of your input segment ID's does not exist in the graph.")?;
+
+        let mut segment_lengths = HashMap::new();
+        let mut blocked = HashSet::new();
+        for segment in &gfa.0.segments {
+            let node_index = graph_indices.seg_id_to_node_index(segment.name.clone())?;
+            segment_lengths.insert(node_index, segment.sequence.len());
+
+            if min_coverage.is_some() || max_coverage.is_some() {
+                let (_, cov) = gfa.node_seq_len_and_cov(segment.name.clone())?;
+                if min_coverage.is_some_and(|m| cov < *m) || max_coverage.is_some_and(|m| cov > *m)
+                {
+                    blocked.insert(node_index);
+                }
+            }
+        }
+        for stop in &stop_segments {
+            let node_index = graph_indices
+                .seg_id_to_node_index(stop.as_bytes().to_vec())
+                .context("One of your stop segment ID's does not exist in the graph.")?;
+            blocked.insert(node_index);
+        }
+
+        let sequences_to_keep = gfa_graph.distance_search(
+            &target_indices,
+            *distance,
+            &segment_lengths,
+            &gfa.link_overlaps()?,
+            &blocked,
+            &graph_indices,
+        )?;
+
+        gfa.print_extract(sequences_to_keep, SortOrder::from_matches(matches));
+
+        return Ok(());
+    }
+
     let (graph_indices, gfa_graph) = gfa.into_ungraph()?;
 
     // get the node index of the target sequence ID.
@@ -61,7 +120,7 @@ pub fn extract(matches: &clap::ArgMatches) -> Result<()> {
         graph_indices,
     )?;
 
-    gfa.print_extract(sequences_to_keep);
+    gfa.print_extract(sequences_to_keep, SortOrder::from_matches(matches));
 
     Ok(())
 }
@@ -1,5 +1,9 @@
 use crate::gfa::gfa_string;
-use crate::{gfa::graph::segments_subgraph, stats, stats::GenomeType};
+use crate::{
+    gfa::graph::{segments_subgraph, SortOrder},
+    stats,
+    stats::GenomeType,
+};
 use anyhow::{Context, Result};
 
 /// Using a combination of GC% of the segments, relative coverage of the segments, and expected length of the mitochondrial genome, extract the putative mitochondrial subgraph.
@@ -14,7 +18,10 @@ pub fn extract_mito(matches: &clap::ArgMatches, genome_type: GenomeType) -> Resu
 
     let subgraph = segments_subgraph(&result.0 .0, result.1);
 
-    println!("{}", gfa_string(&subgraph));
+    println!(
+        "{}",
+        gfa_string(&subgraph, SortOrder::from_matches(matches))
+    );
 
     Ok(())
 }
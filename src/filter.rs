@@ -0,0 +1,259 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::gfa::gfa::GFAtk;
+use crate::gfa::gfa_string;
+use crate::gfa::graph::{segments_subgraph, SortOrder};
+use crate::load::{load_gfa, load_gfa_stdin};
+use crate::segments::segment_stats;
+use crate::utils::{self, get_edge_coverage, reverse_orientation};
+use anyhow::{bail, Context, Result};
+use gfa::gfa::Orientation;
+
+/// The coverage thresholds of `gfatk filter`. Any left as `None` are not applied.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FilterThresholds {
+    /// Minimum segment coverage (`ll`).
+    pub min_coverage: Option<f32>,
+    /// Minimum segment coverage, as a fraction of the length weighted median
+    /// segment coverage of its component.
+    pub min_relative_coverage: Option<f32>,
+    /// Minimum link coverage (`ec`).
+    pub min_link_coverage: Option<i64>,
+    /// Minimum link coverage, as a fraction of the median link coverage of its component.
+    pub min_relative_link_coverage: Option<f32>,
+}
+
+/// Whether a [`Removed`] element is a segment or a link.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RemovedKind {
+    /// A segment, and the links on it.
+    Segment,
+    /// A link, and its mirror.
+    Link,
+}
+
+impl std::fmt::Display for RemovedKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RemovedKind::Segment => write!(f, "segment"),
+            RemovedKind::Link => write!(f, "link"),
+        }
+    }
+}
+
+/// A segment or link removed by [`filter_gfa`].
+#[derive(Clone, Debug)]
+pub struct Removed {
+    /// Whether a segment or a link was removed.
+    pub kind: RemovedKind,
+    /// The segment name, or the link as oriented segments, e.g. `1+,2-`.
+    pub element: String,
+    /// Index of the weakly connected component, as in `gfatk stats`.
+    pub component: usize,
+    /// The coverage of the segment or link.
+    pub coverage: f32,
+    /// The median coverage of the segments or links in the component.
+    pub component_median: f32,
+    /// Which threshold the element fell below.
+    pub reason: String,
+}
+
+/// A link, as its oriented segments.
+type LinkKey = (Vec<u8>, Orientation, Vec<u8>, Orientation);
+
+/// Remove segments, and then links, below the coverage thresholds.
+///
+/// Components are those of the input GFA. Links are compared against the
+/// median link coverage of their component (counting a link and its mirror once),
+/// and a link and its mirror are removed together. Links on removed segments are
+/// removed with them, and are not reported.
+pub fn filter_gfa(gfa: &GFAtk, thresholds: FilterThresholds) -> Result<(GFAtk, Vec<Removed>)> {
+    let stats = segment_stats(gfa)?;
+    let mut removed = Vec::new();
+
+    let mut component_of = HashMap::new();
+    let mut keep = Vec::new();
+    for stat in &stats {
+        component_of.insert(stat.name.as_slice(), stat.component);
+
+        let reason = if thresholds.min_coverage.is_some_and(|c| stat.coverage < c) {
+            Some("min-coverage")
+        } else if thresholds
+            .min_relative_coverage
+            .is_some_and(|c| stat.coverage < c * stat.component_coverage)
+        {
+            Some("min-relative-coverage")
+        } else {
+            None
+        };
+
+        match reason {
+            Some(reason) => removed.push(Removed {
+                kind: RemovedKind::Segment,
+                element: String::from_utf8_lossy(&stat.name).to_string(),
+                component: stat.component,
+                coverage: stat.coverage,
+                component_median: stat.component_coverage,
+                reason: reason.to_string(),
+            }),
+            None => keep.push(stat.name.clone()),
+        }
+    }
+
+    // the median link coverage of each component
+    let mut link_coverages = Vec::new();
+    let mut component_links: HashMap<usize, Vec<(f32, usize)>> = HashMap::new();
+    for link in gfa.unique_links() {
+        let ec = get_edge_coverage(&link.optional)?;
+        let component = *component_of
+            .get(link.from_segment.as_slice())
+            .context("Link from a segment not found in GFA.")?;
+        component_links
+            .entry(component)
+            .or_default()
+            .push((ec as f32, 1));
+        link_coverages.push((link, ec));
+    }
+    let component_medians: HashMap<usize, f32> = component_links
+        .iter()
+        .map(|(c, v)| (*c, utils::weighted_median(v).unwrap_or(0.0)))
+        .collect();
+
+    let kept: HashSet<&[u8]> = keep.iter().map(|s| s.as_slice()).collect();
+    let mut removed_links: HashSet<LinkKey> = HashSet::new();
+
+    for (link, ec) in link_coverages {
+        let (from, from_orient) = (link.from_segment.as_slice(), link.from_orient);
+        let (to, to_orient) = (link.to_segment.as_slice(), link.to_orient);
+        if !kept.contains(from) || !kept.contains(to) {
+            continue;
+        }
+        let component = component_of[from];
+        let component_median = component_medians[&component];
+
+        let reason = if thresholds.min_link_coverage.is_some_and(|c| ec < c) {
+            Some("min-link-coverage")
+        } else if thresholds
+            .min_relative_link_coverage
+            .is_some_and(|c| (ec as f32) < c * component_median)
+        {
+            Some("min-relative-link-coverage")
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            removed.push(Removed {
+                kind: RemovedKind::Link,
+                element: format!(
+                    "{}{},{}{}",
+                    String::from_utf8_lossy(from),
+                    from_orient,
+                    String::from_utf8_lossy(to),
+                    to_orient
+                ),
+                component,
+                coverage: ec as f32,
+                component_median,
+                reason: reason.to_string(),
+            });
+            removed_links.insert((from.to_vec(), from_orient, to.to_vec(), to_orient));
+            removed_links.insert((
+                to.to_vec(),
+                reverse_orientation(to_orient),
+                from.to_vec(),
+                reverse_orientation(from_orient),
+            ));
+        }
+    }
+
+    let mut filtered = segments_subgraph(&gfa.0, keep);
+    filtered.links.retain(|l| {
+        !removed_links.contains(&(
+            l.from_segment.clone(),
+            l.from_orient,
+            l.to_segment.clone(),
+            l.to_orient,
+        ))
+    });
+
+    Ok((GFAtk(filtered), removed))
+}
+
+/// Filter low coverage segments and links from a GFA.
+///
+/// Thresholds are absolute, or relative to the median coverage of the component.
+/// The filtered GFA is printed to STDOUT, and optionally a TSV of the removed
+/// segments and links is written to `--report`.
+///
+/// For example:
+/// ```bash
+/// gfatk filter in.gfa --min-relative-link-coverage 0.1 | gfatk linear > out.fa
+/// ```
+pub fn filter(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let report_file = matches.get_one::<PathBuf>("report");
+    let thresholds = FilterThresholds {
+        min_coverage: matches.get_one::<f32>("min-coverage").copied(),
+        min_relative_coverage: matches.get_one::<f32>("min-relative-coverage").copied(),
+        min_link_coverage: matches.get_one::<i64>("min-link-coverage").copied(),
+        min_relative_link_coverage: matches
+            .get_one::<f32>("min-relative-link-coverage")
+            .copied(),
+    };
+
+    let gfa: GFAtk = match gfa_file {
+        Some(f) => {
+            let ext = f.extension();
+            match ext {
+                Some(e) => {
+                    if e == "gfa" {
+                        GFAtk(load_gfa(f)?)
+                    } else {
+                        bail!("Input is not a GFA.")
+                    }
+                }
+                None => bail!("Could not read file."),
+            }
+        }
+        None => match utils::is_stdin() {
+            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            false => bail!("No input from STDIN. Run `gfatk filter -h` for help."),
+        },
+    };
+
+    let gfa = gfa.sorted(matches);
+    let (filtered, removed) = filter_gfa(&gfa, thresholds)?;
+
+    let segments = removed
+        .iter()
+        .filter(|r| r.kind == RemovedKind::Segment)
+        .count();
+    eprintln!(
+        "[+]\tRemoved {} segment(s) and {} link(s).",
+        segments,
+        removed.len() - segments
+    );
+
+    if let Some(report_file) = report_file {
+        let mut report =
+            String::from("type\telement\tcomponent\tcoverage\tcomponent_median\treason\n");
+        for r in &removed {
+            report += &format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\n",
+                r.kind, r.element, r.component, r.coverage, r.component_median, r.reason
+            );
+        }
+        fs::write(report_file, report)
+            .with_context(|| format!("Could not write {:?}", report_file.as_os_str()))?;
+    }
+
+    println!(
+        "{}",
+        gfa_string(&filtered.0, SortOrder::from_matches(matches))
+    );
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use crate::gfa::{
     gfa_string,
-    graph::{segments_subgraph, GFAdigraph, GFAungraph},
+    graph::{segments_subgraph, sort_gfa, GFAdigraph, GFAungraph, SortOrder},
 };
 use crate::path::GFAPath;
 use crate::stats::GenomeType;
@@ -8,10 +8,12 @@ use crate::utils::{
     self, get_edge_coverage, parse_cigar, reverse_complement, GFAGraphLookups, GFAGraphPair,
 };
 use anyhow::{bail, Context, Result};
-use gfa::gfa::{Orientation, GFA};
+use gfa::gfa::{Link, Orientation, GFA};
 use gfa::optfields::{OptFieldVal, OptionalFields};
+use petgraph::algo::tarjan_scc;
 use petgraph::graph::{Graph, NodeIndex, UnGraph};
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// A wrapper around GFA from the gfa crate
 /// TODO: make GFAtk generic for any segment name, not just usize.
@@ -19,6 +21,26 @@ use std::collections::HashMap;
 pub struct GFAtk(pub GFA<Vec<u8>, OptionalFields>);
 
 impl GFAtk {
+    /// Wrap a GFA, optionally canonicalising its links (as in
+    /// [`GFAtk::normalize_links`]) so that graphs built from it have one edge for
+    /// each adjacency.
+    pub fn new(gfa: GFA<Vec<u8>, OptionalFields>, normalize_links: bool) -> Result<Self> {
+        let gfa = GFAtk(gfa);
+        match normalize_links {
+            true => Ok(gfa.normalize_links(false)?.0),
+            false => Ok(gfa),
+        }
+    }
+
+    /// The GFA sorted into the `--order` of the command line (by name, by default).
+    ///
+    /// Commands which number components, or other groups of segments, number them
+    /// on the sorted GFA, so that the numbers are the same every run, and the same
+    /// as in `gfatk stats`.
+    pub fn sorted(&self, matches: &clap::ArgMatches) -> GFAtk {
+        GFAtk(sort_gfa(&self.0, SortOrder::from_matches(matches)))
+    }
+
     /// Returns a tuple of GFAGraphLookups (a struct of indices/node names)
     /// and an undirected GFA graph structure.
     pub fn into_ungraph(&self) -> Result<(GFAGraphLookups, GFAungraph)> {
@@ -94,12 +116,24 @@ impl GFAtk {
         Ok((graph_indices, GFAdigraph(gfa_graph)))
     }
 
+    /// Returns the overlap length of each link in the GFA.
+    ///
+    /// These are in the order of the links, and so can be indexed by
+    /// the edge indices of [`GFAtk::into_digraph`].
+    pub fn link_overlaps(&self) -> Result<Vec<usize>> {
+        self.0
+            .links
+            .iter()
+            .map(|link| parse_cigar(&link.overlap))
+            .collect()
+    }
+
     /// A method to print a GFA to STDOUT, given a vector of sequence ID's to keep.
-    pub fn print_extract(&self, sequences_to_keep: Vec<Vec<u8>>) {
+    pub fn print_extract(&self, sequences_to_keep: Vec<Vec<u8>>, order: SortOrder) {
         let gfa = &self.0;
         let subgraph_gfa = GFAtk(segments_subgraph(gfa, sequences_to_keep));
 
-        print!("{}", gfa_string(&subgraph_gfa.0));
+        print!("{}", gfa_string(&subgraph_gfa.0, order));
     }
 
     /// Returns the overlaps between all the segments in a GFA.
@@ -263,24 +297,178 @@ impl GFAtk {
         Ok(ll)
     }
 
-    fn get_coverage(&self) -> Result<f32> {
-        let gfa = &self.0;
+    /// The links of the GFA, counting a link and its mirror (and any duplicate
+    /// links) once.
+    ///
+    /// MBG writes both a link and its mirror, e.g. `1+ -> 2+` and `2- -> 1-`.
+    pub fn unique_links(&self) -> Vec<&Link<Vec<u8>, OptionalFields>> {
+        let mut seen_links = HashSet::new();
+        let mut links = Vec::new();
+
+        for link in &self.0.links {
+            let forward = (
+                link.from_segment.as_slice(),
+                link.from_orient,
+                link.to_segment.as_slice(),
+                link.to_orient,
+            );
+            let mirror = (
+                link.to_segment.as_slice(),
+                utils::reverse_orientation(link.to_orient),
+                link.from_segment.as_slice(),
+                utils::reverse_orientation(link.from_orient),
+            );
+            if seen_links.contains(&forward) || seen_links.contains(&mirror) {
+                continue;
+            }
+            seen_links.insert(forward);
+            links.push(link);
+        }
 
-        let ll_tag: [u8; 2] = [108, 108];
-        let mut ll_tag_vec = Vec::new();
+        links
+    }
 
-        for seg in &gfa.segments {
-            let opts = &seg.optional;
-            for opt in opts {
-                if opt.tag == ll_tag {
-                    ll_tag_vec.push(Self::parse_coverage_opt(&opt.value)?);
+    /// The segment ends joined to each segment end by a link, counting a link and
+    /// its mirror once (as in [`GFAtk::unique_links`]).
+    ///
+    /// A hairpin joins a segment end to itself, and is listed once.
+    pub fn segment_end_adjacency(&self) -> HashMap<SegmentEnd<'_>, Vec<SegmentEnd<'_>>> {
+        let mut adjacency: HashMap<SegmentEnd, Vec<SegmentEnd>> = HashMap::new();
+        for link in self.unique_links() {
+            let from = exit_end(&link.from_segment, link.from_orient);
+            let to = entry_end(&link.to_segment, link.to_orient);
+            adjacency.entry(from).or_default().push(to);
+            if from != to {
+                adjacency.entry(to).or_default().push(from);
+            }
+        }
+        adjacency
+    }
+
+    /// Canonicalise the links of the GFA, so that each adjacency is written once.
+    ///
+    /// A link and its mirror (e.g. `1+ -> 2+` and `2- -> 1-`) are one adjacency, and
+    /// the first written is kept. Repeated links are merged, keeping the highest
+    /// edge coverage. With `mirrors`, both the link and its mirror are written
+    /// instead (as MBG does), which adds any missing mirrors, with the overlap
+    /// reversed. Returns the new GFA, and a [`LinkNormalization`] recording what
+    /// was found.
+    pub fn normalize_links(&self, mirrors: bool) -> Result<(GFAtk, LinkNormalization)> {
+        type LinkKey<'a> = (&'a [u8], Orientation, &'a [u8], Orientation);
+        // the first link written, the times written as is and as its mirror, and the coverages
+        type Adjacency<'a> = (&'a Link<Vec<u8>, OptionalFields>, usize, usize, Vec<i64>);
+
+        let mut order: Vec<LinkKey> = Vec::new();
+        let mut adjacencies: HashMap<LinkKey, Adjacency> = HashMap::new();
+
+        for link in &self.0.links {
+            let forward = (
+                link.from_segment.as_slice(),
+                link.from_orient,
+                link.to_segment.as_slice(),
+                link.to_orient,
+            );
+            let mirror = (
+                link.to_segment.as_slice(),
+                utils::reverse_orientation(link.to_orient),
+                link.from_segment.as_slice(),
+                utils::reverse_orientation(link.from_orient),
+            );
+            let ec = get_edge_coverage(&link.optional).ok();
+
+            let entry = if let Some(entry) = adjacencies.get_mut(&forward) {
+                entry.1 += 1;
+                entry
+            } else if let Some(entry) = adjacencies.get_mut(&mirror) {
+                entry.2 += 1;
+                entry
+            } else {
+                order.push(forward);
+                adjacencies
+                    .entry(forward)
+                    .or_insert((link, 1, 0, Vec::new()))
+            };
+            entry.3.extend(ec);
+        }
+
+        let mut normalization = LinkNormalization::default();
+        let mut links = Vec::new();
+
+        for key in order {
+            let (link, written, mirrored, coverages) = &adjacencies[&key];
+            let (from, from_orient, to, to_orient) = key;
+            // hairpins are their own mirror
+            let self_mirror = from == to && from_orient == utils::reverse_orientation(to_orient);
+            let name = format!(
+                "{}{},{}{}",
+                String::from_utf8_lossy(from),
+                from_orient,
+                String::from_utf8_lossy(to),
+                to_orient
+            );
+
+            if *written > 1 || *mirrored > 1 {
+                normalization.duplicates.push(name.clone());
+            }
+            if coverages.iter().any(|c| *c != coverages[0]) {
+                normalization
+                    .coverage_mismatches
+                    .push((name.clone(), coverages.clone()));
+            }
+            if *mirrored > 0 {
+                normalization.mirror_pairs += 1;
+            } else if !self_mirror {
+                normalization.missing_mirrors.push(name);
+            }
+
+            let mut link = (*link).clone();
+            if let (Some(ec), Some(field)) = (coverages.iter().max(), link.optional.first_mut()) {
+                if matches!(&field.tag, b"ec" | b"EC") {
+                    field.value = OptFieldVal::Int(*ec);
                 }
             }
+            if mirrors && !self_mirror {
+                let mut mirror = link.clone();
+                mirror.from_segment = link.to_segment.clone();
+                mirror.from_orient = utils::reverse_orientation(link.to_orient);
+                mirror.to_segment = link.from_segment.clone();
+                mirror.to_orient = utils::reverse_orientation(link.from_orient);
+                mirror.overlap = utils::reverse_cigar(&link.overlap)?;
+                links.push(link);
+                links.push(mirror);
+            } else {
+                links.push(link);
+            }
+        }
+
+        normalization.links_in = self.0.links.len();
+        normalization.links_out = links.len();
+
+        let mut gfa = self.0.clone();
+        gfa.links = links;
+
+        Ok((GFAtk(gfa), normalization))
+    }
+
+    /// The number of bases to trim from the start and end (in forward
+    /// orientation) of each segment, so that each overlap is counted once.
+    ///
+    /// For each link (counting a link and its mirror once), the overlap is
+    /// removed from the end of the `to` segment that the link enters.
+    /// Where several links enter the same end, the longest overlap is trimmed.
+    pub fn overlap_trims(&self) -> Result<HashMap<Vec<u8>, (usize, usize)>> {
+        let mut trims: HashMap<Vec<u8>, (usize, usize)> = HashMap::new();
+
+        for link in self.unique_links() {
+            let overlap = parse_cigar(&link.overlap)?;
+            let trim = trims.entry(link.to_segment.clone()).or_default();
+            match link.to_orient {
+                Orientation::Forward => trim.0 = trim.0.max(overlap),
+                Orientation::Backward => trim.1 = trim.1.max(overlap),
+            }
         }
-        let len = ll_tag_vec.len() as f32;
-        let sum: f32 = ll_tag_vec.iter().fold(0.0, |a, b| a + **b);
 
-        Ok(sum / len)
+        Ok(trims)
     }
 
     /// Return the coverage and sequence length for a segment, given a segment name.
@@ -314,46 +502,212 @@ impl GFAtk {
 
     /// The internal function called in `gfatk stats`.
     ///
-    /// Returns average GC%, average coverage, and total sequence length for a GFA (sub)graph.
-    pub fn sequence_stats(
-        &self,
-        genome_type: GenomeType,
-        tabular: bool,
-    ) -> Result<(f32, f32, usize)> {
+    /// Returns the [`SequenceStats`] of a GFA (sub)graph. GC% and coverage are
+    /// weighted by segment length, once the overlaps from [`GFAtk::overlap_trims`]
+    /// are removed, and are 0 if that leaves no sequence.
+    pub fn sequence_stats(&self, genome_type: GenomeType, tabular: bool) -> Result<SequenceStats> {
         let gfa = &self.0;
 
-        let cov = Self::get_coverage(self)?;
+        let trims = self.overlap_trims()?;
 
-        let mut total_overlap_length = 0;
-        for link in &gfa.links {
-            total_overlap_length += parse_cigar(&link.overlap)?;
-        }
-
-        let mut total_sequence_length = 0;
-        let mut gc_vec = Vec::new();
+        let mut sequence_length = 0;
+        let mut corrected_sequence_length = 0;
+        let mut gc_sum = 0.0;
+        let mut cov_sum = 0.0;
 
         for segment in &gfa.segments {
             let seq = &segment.sequence;
-            total_sequence_length += seq.len();
+            sequence_length += seq.len();
+
+            let (trim_start, trim_end) = trims.get(&segment.name).copied().unwrap_or((0, 0));
+            let start = trim_start.min(seq.len());
+            let end = seq.len().saturating_sub(trim_end).max(start);
+            let corrected = &seq[start..end];
 
-            let gc = utils::gc_content(seq);
-            gc_vec.push(gc);
+            let (_, cov) = self.node_seq_len_and_cov(segment.name.clone())?;
+
+            corrected_sequence_length += corrected.len();
+            if !corrected.is_empty() {
+                gc_sum += utils::gc_content(corrected) * corrected.len() as f32;
+            }
+            cov_sum += cov * corrected.len() as f32;
         }
 
-        let avg_gc = gc_vec.iter().sum::<f32>() / gc_vec.len() as f32;
+        // overlaps can remove the whole sequence
+        let (gc, coverage) = match corrected_sequence_length {
+            0 => (0.0, 0.0),
+            length => (gc_sum / length as f32, cov_sum / length as f32),
+        };
 
         if !tabular && genome_type == GenomeType::None {
-            println!("\tTotal sequence length:\t{}", total_sequence_length);
-            println!("\tTotal sequence overlap length:\t{}", total_overlap_length);
+            println!("\tTotal sequence length:\t{}", sequence_length);
+            println!(
+                "\tTotal sequence overlap length:\t{}",
+                sequence_length - corrected_sequence_length
+            );
             println!(
                 "\tSequence length minus overlaps:\t{}",
-                total_sequence_length as i32 - total_overlap_length as i32
+                corrected_sequence_length
+            );
+            println!(
+                "\tGC content of total sequence (length weighted, minus overlaps):\t{}",
+                gc
             );
-            println!("\tGC content of total sequence:\t{}", avg_gc);
-            println!("\tAverage coverage of total segments:\t{}", cov);
+            println!(
+                "\tCoverage of total segments (length weighted, minus overlaps):\t{}",
+                coverage
+            );
+        }
+
+        Ok(SequenceStats {
+            gc,
+            coverage,
+            sequence_length,
+            corrected_sequence_length,
+        })
+    }
+
+    /// The topology statistics of a GFA (sub)graph.
+    ///
+    /// Each link and its mirror are counted once. Segment ends are the start and
+    /// end of a segment in its forward orientation.
+    pub fn topology_stats(&self) -> Result<TopologyStats> {
+        let gfa = &self.0;
+
+        // N50/L50 of the segment lengths
+        let mut lengths: Vec<usize> = gfa.segments.iter().map(|s| s.sequence.len()).collect();
+        lengths.sort_unstable_by(|a, b| b.cmp(a));
+        let total: usize = lengths.iter().sum();
+        let (mut n50, mut l50, mut cumulative) = (0, 0, 0);
+        for (i, len) in lengths.iter().enumerate() {
+            cumulative += len;
+            if cumulative * 2 >= total {
+                n50 = *len;
+                l50 = i + 1;
+                break;
+            }
+        }
+
+        // the neighbouring segment ends of each segment end
+        let adjacency = self.segment_end_adjacency();
+
+        let (mut self_loops, mut hairpins) = (0, 0);
+        for link in self.unique_links() {
+            if link.from_segment == link.to_segment {
+                if link.from_orient == link.to_orient {
+                    self_loops += 1;
+                } else {
+                    hairpins += 1;
+                }
+            }
+        }
+
+        let mut end_degrees = BTreeMap::new();
+        let mut dead_ends = 0;
+        let mut repeat_nodes = 0;
+        // segments with exactly one neighbour on each end, keyed on those neighbours
+        let mut bubble_branches: HashMap<(SegmentEnd, SegmentEnd), usize> = HashMap::new();
+
+        for segment in &gfa.segments {
+            let name = segment.name.as_slice();
+            let start = adjacency
+                .get(&(name, false))
+                .map_or(&[][..], |v| v.as_slice());
+            let end = adjacency
+                .get(&(name, true))
+                .map_or(&[][..], |v| v.as_slice());
+
+            for degree in [start.len(), end.len()] {
+                *end_degrees.entry(degree).or_insert(0) += 1;
+                if degree == 0 {
+                    dead_ends += 1;
+                }
+            }
+            if start.len() >= 2 && end.len() >= 2 {
+                repeat_nodes += 1;
+            }
+            if start.len() == 1 && end.len() == 1 && start[0].0 != name && end[0].0 != name {
+                let key = if start[0] <= end[0] {
+                    (start[0], end[0])
+                } else {
+                    (end[0], start[0])
+                };
+                *bubble_branches.entry(key).or_insert(0) += 1;
+            }
+        }
+
+        let simple_bubbles = bubble_branches.values().filter(|n| **n >= 2).count();
+
+        Ok(TopologyStats {
+            n50,
+            l50,
+            largest_segment: lengths.first().copied().unwrap_or(0),
+            end_degrees,
+            dead_ends,
+            self_loops,
+            hairpins,
+            simple_bubbles,
+            repeat_nodes,
+        })
+    }
+
+    /// Whether a GFA (sub)graph is circular, linear or complex.
+    ///
+    /// The graph is circular if there is an orientation consistent closed walk through
+    /// every segment. That is, a strongly connected component of the graph of oriented
+    /// segments (with each link added in both directions) contains every segment in at
+    /// least one orientation, and at least one link. Otherwise, the graph is linear if it
+    /// is a simple path (no more than one link on each segment end), and complex if not.
+    pub fn circularity(&self) -> Circularity {
+        let gfa = &self.0;
+
+        let mut graph: Graph<(&[u8], Orientation), ()> = Graph::new();
+        let mut oriented_nodes = HashMap::new();
+        for segment in &gfa.segments {
+            for orientation in [Orientation::Forward, Orientation::Backward] {
+                let key = (segment.name.as_slice(), orientation);
+                oriented_nodes.insert(key, graph.add_node(key));
+            }
         }
 
-        Ok((avg_gc, cov, total_sequence_length))
+        let mut end_links: HashMap<(&[u8], bool), usize> = HashMap::new();
+        for link in &gfa.links {
+            let (from, to) = (link.from_segment.as_slice(), link.to_segment.as_slice());
+            let edges = [
+                ((from, link.from_orient), (to, link.to_orient)),
+                (
+                    (to, utils::reverse_orientation(link.to_orient)),
+                    (from, utils::reverse_orientation(link.from_orient)),
+                ),
+            ];
+            for (a, b) in edges {
+                if let (Some(a), Some(b)) = (oriented_nodes.get(&a), oriented_nodes.get(&b)) {
+                    graph.update_edge(*a, *b, ());
+                }
+            }
+        }
+        for link in self.unique_links() {
+            *end_links
+                .entry((&link.from_segment, link.from_orient == Orientation::Forward))
+                .or_default() += 1;
+            *end_links
+                .entry((&link.to_segment, link.to_orient == Orientation::Backward))
+                .or_default() += 1;
+        }
+
+        for component in tarjan_scc(&graph) {
+            let has_link = component.len() > 1 || graph.contains_edge(component[0], component[0]);
+            let segments: HashSet<&[u8]> = component.iter().map(|n| graph[*n].0).collect();
+            if has_link && segments.len() == gfa.segments.len() {
+                return Circularity::Circular;
+            }
+        }
+
+        if end_links.values().all(|n| *n <= 1) {
+            Circularity::Linear
+        } else {
+            Circularity::Complex
+        }
     }
 
     /// Returns a `HashMap` of relative coverage of each node (segment) in the GFA.
@@ -545,6 +899,96 @@ impl GFAtk {
     }
 }
 
+/// Sequence statistics of a GFA (sub)graph.
+#[derive(Clone, Copy, Debug)]
+pub struct SequenceStats {
+    /// GC% weighted by segment length, minus overlaps.
+    pub gc: f32,
+    /// Coverage weighted by segment length, minus overlaps.
+    pub coverage: f32,
+    /// The raw sequence length, the sum of the segment lengths.
+    pub sequence_length: usize,
+    /// The sequence length once overlaps between segments are removed.
+    pub corrected_sequence_length: usize,
+}
+
+/// The shape of a GFA (sub)graph, from [`GFAtk::circularity`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Circularity {
+    /// There is a closed walk through every segment.
+    Circular,
+    /// A simple path, with two ends.
+    Linear,
+    /// Neither circular nor linear, e.g. with branches and tips.
+    Complex,
+}
+
+impl std::fmt::Display for Circularity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Circularity::Circular => write!(f, "circular"),
+            Circularity::Linear => write!(f, "linear"),
+            Circularity::Complex => write!(f, "complex"),
+        }
+    }
+}
+
+/// A segment name, and `true` for its end or `false` for its start.
+pub type SegmentEnd<'a> = (&'a [u8], bool);
+
+/// The end of a segment that a walk leaves from, in a given orientation.
+pub fn exit_end(name: &[u8], orientation: Orientation) -> SegmentEnd<'_> {
+    (name, orientation == Orientation::Forward)
+}
+
+/// The end of a segment that a walk enters at, in a given orientation.
+pub fn entry_end(name: &[u8], orientation: Orientation) -> SegmentEnd<'_> {
+    (name, orientation == Orientation::Backward)
+}
+
+/// Topology statistics of a GFA (sub)graph.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TopologyStats {
+    /// The N50 of the segment lengths.
+    pub n50: usize,
+    /// The L50 of the segment lengths.
+    pub l50: usize,
+    /// The length of the largest segment.
+    pub largest_segment: usize,
+    /// The number of segment ends with each number of links.
+    pub end_degrees: BTreeMap<usize, usize>,
+    /// The number of segment ends with no links.
+    pub dead_ends: usize,
+    /// Links joining a segment to itself in the same orientation.
+    pub self_loops: usize,
+    /// Links joining a segment to its own reverse.
+    pub hairpins: usize,
+    /// Pairs (or more) of segments with a single link on each end, to the same segment ends.
+    pub simple_bubbles: usize,
+    /// Segments with two or more links on both ends.
+    pub repeat_nodes: usize,
+}
+
+/// What was found canonicalising the links of a GFA, from [`GFAtk::normalize_links`].
+///
+/// Links are named by the first written, e.g. `1+,2-`.
+#[derive(Clone, Debug, Default)]
+pub struct LinkNormalization {
+    /// The number of links in the input.
+    pub links_in: usize,
+    /// The number of links in the output.
+    pub links_out: usize,
+    /// Adjacencies written as both a link and its mirror.
+    pub mirror_pairs: usize,
+    /// Adjacencies written more than once in the same direction.
+    pub duplicates: Vec<String>,
+    /// Adjacencies written more than once with different edge coverages, and the coverages.
+    pub coverage_mismatches: Vec<(String, Vec<i64>)>,
+    /// Links whose mirror is missing. Hairpins are their own mirror.
+    pub missing_mirrors: Vec<String>,
+}
+
 /// Overlap from one segment to another.
 pub struct Overlap {
     /// From segment forward.
@@ -618,11 +1062,67 @@ mod tests {
         let gfa = make_gfa("./tests/test_linear.gfa");
 
         // could be mitochondria/chloroplast
-        let (gc, cov, len) = gfa.sequence_stats(GenomeType::Mitochondria, false).unwrap();
+        let stats = gfa.sequence_stats(GenomeType::Mitochondria, false).unwrap();
+
+        // ACCTT + (TCAAGG minus 4bp overlap) + (CTTGATT minus 5bp overlap)
+        assert_eq!(stats.sequence_length, 18);
+        assert_eq!(stats.corrected_sequence_length, 9);
+        assert!((stats.gc - 3.0 / 9.0).abs() < 1e-6);
+        assert!((stats.coverage - 330.0 / 9.0).abs() < 1e-4);
+
+        // a self loop overlapping the whole segment leaves no sequence
+        let gfa = make_gfa("./tests/test_full_overlap.gfa");
+        let stats = gfa.sequence_stats(GenomeType::Mitochondria, false).unwrap();
+        assert_eq!(stats.corrected_sequence_length, 0);
+        assert_eq!((stats.gc, stats.coverage), (0.0, 0.0));
+    }
 
-        assert!(cov == 40.0);
-        assert!(gc == 0.39523807);
-        assert!(len == 18);
+    #[test]
+    fn test_gfa_topology_stats() {
+        // 1 -> (2 | 3) -> 4 -> 5 (self loop) -> 6, and a hairpin on 1
+        let gfa = make_gfa("./tests/test_topology.gfa");
+
+        let topology = gfa.topology_stats().unwrap();
+
+        assert_eq!(topology.n50, 10);
+        assert_eq!(topology.l50, 2);
+        assert_eq!(topology.largest_segment, 10);
+        assert_eq!(
+            topology.end_degrees,
+            BTreeMap::from([(0, 1), (1, 7), (2, 4)])
+        );
+        assert_eq!(topology.dead_ends, 1);
+        assert_eq!(topology.self_loops, 1);
+        assert_eq!(topology.hairpins, 1);
+        assert_eq!(topology.simple_bubbles, 1);
+        // 5 (the self loop); the hairpin is one link on the start of 1
+        assert_eq!(topology.repeat_nodes, 1);
+    }
+
+    #[test]
+    fn test_gfa_circularity() {
+        // 1+ -> 2+ -> 1+
+        assert_eq!(
+            make_gfa("./tests/test_circular.gfa").circularity(),
+            Circularity::Circular
+        );
+        // a self loop on a single segment
+        assert_eq!(
+            make_gfa("./tests/test_single_segment.gfa").circularity(),
+            Circularity::Circular
+        );
+        // 1+ -> 2+ and 3+ -> 4+, each with their mirror
+        let gfa = make_gfa("./tests/test_markers.gfa");
+        let subgraph = GFAtk(segments_subgraph(
+            &gfa.0,
+            vec![b"1".to_vec(), b"2".to_vec()],
+        ));
+        assert_eq!(subgraph.circularity(), Circularity::Linear);
+        // 11+ links to both 12- and 13+
+        assert_eq!(
+            make_gfa("./tests/test_linear.gfa").circularity(),
+            Circularity::Complex
+        );
     }
 
     #[test]
@@ -1,19 +1,20 @@
 use crate::gfa::gfa::GFAtk;
-use crate::utils::{format_usize_to_kb, GFAGraphLookups};
+use crate::utils::{format_usize_to_kb, natural_cmp, reverse_orientation, GFAGraphLookups};
 use anyhow::{bail, Context, Result};
 use gfa::gfa::Orientation;
 use gfa::gfa::GFA;
 use gfa::optfields::OptFields;
 use itertools::Itertools;
 use petgraph::{
-    graph::{Graph, IndexType, NodeIndex},
+    graph::{EdgeIndex, Graph, IndexType, NodeIndex},
     visit::{EdgeRef, IntoNodeIdentifiers, IntoNodeReferences, NodeIndexable, NodeRef},
     Directed,
-    Direction::Outgoing,
+    Direction::{Incoming, Outgoing},
     Undirected,
 };
+use std::cmp::Reverse;
 use std::collections::HashMap;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, BinaryHeap, HashSet};
 
 /// A wrapper of petgraph's undirected `Graph` struct, applied to a GFA. No weights.
 pub struct GFAungraph(pub Graph<Vec<u8>, (), Undirected>);
@@ -87,7 +88,7 @@ impl GFAdigraph {
         for node in gfa_graph.node_references() {
             let e = gfa_graph.to_index(node.id());
             let w = node.weight();
-            let w_d = std::str::from_utf8(&w)?;
+            let w_d = std::str::from_utf8(w)?;
             let meta = gfa.node_seq_len_and_cov(w.to_vec())?;
             println!(
                 // see https://stackoverflow.com/questions/20516143/graphviz-dot-different-fontsizes-in-same-label
@@ -201,6 +202,7 @@ impl GFAdigraph {
     /// This function will generate the longest path through the GFA, by
     /// filtering the output of `all_paths`, and choosing the path with
     /// the highest cumulative edge coverage.
+    #[allow(clippy::type_complexity)]
     pub fn all_paths_all_node_pairs(
         &self,
         graph_indices: &GFAGraphLookups,
@@ -403,8 +405,7 @@ impl GFAdigraph {
                 map.insert(path_orientation_tuple, path_coverage);
             }
 
-            let highest_coverage_path_op =
-                map.iter().max_by(|a, b| a.1.cmp(b.1)).map(|(k, v)| (k, v));
+            let highest_coverage_path_op = map.iter().max_by(|a, b| a.1.cmp(b.1));
 
             // explicit error out here
             let highest_coverage_path = match highest_coverage_path_op {
@@ -470,6 +471,115 @@ impl GFAdigraph {
         Ok((final_path.0.to_vec(), difference_ids?, fasta_header))
     }
 
+    /// Returns the oriented segments which can follow `node`, when `node` is
+    /// traversed in `orientation`.
+    ///
+    /// A link `A+ -> B-` can be walked as written, or as its mirror `B+ -> A-`, so
+    /// both the outgoing and incoming edges of `node` are considered. This means a GFA
+    /// need not contain both directions of each link. Each neighbour is returned with
+    /// the orientation it is entered in, and the index of the edge walked.
+    pub fn oriented_neighbours(
+        &self,
+        node: NodeIndex,
+        orientation: Orientation,
+    ) -> Vec<(NodeIndex, Orientation, EdgeIndex)> {
+        let gfa_graph = &self.0;
+        let mut neighbours: Vec<(NodeIndex, Orientation, EdgeIndex)> = Vec::new();
+
+        // the links as written
+        for edge in gfa_graph.edges_directed(node, Outgoing) {
+            let (from_orient, to_orient, _) = *edge.weight();
+            if from_orient == orientation {
+                neighbours.push((edge.target(), to_orient, edge.id()));
+            }
+        }
+        // and the mirrors of the links coming in
+        for edge in gfa_graph.edges_directed(node, Incoming) {
+            let (from_orient, to_orient, _) = *edge.weight();
+            if to_orient == reverse_orientation(orientation) {
+                let next = (edge.source(), reverse_orientation(from_orient));
+                if !neighbours.iter().any(|(n, o, _)| (*n, *o) == next) {
+                    neighbours.push((next.0, next.1, edge.id()));
+                }
+            }
+        }
+
+        neighbours
+    }
+
+    /// The algorithm called in `gfatk extract` when a distance is supplied.
+    ///
+    /// Starting from both ends of each seed segment, the graph is expanded along
+    /// orientation consistent links in order of increasing distance. A segment is
+    /// collected if it starts within `distance` bp of a seed. Passing through a segment
+    /// costs its length, less the overlap of the link used to enter the next one.
+    ///
+    /// `segment_lengths` is indexed by node, and `overlaps` by edge. Segments in `blocked`
+    /// are never entered.
+    pub fn distance_search(
+        &self,
+        seeds: &[NodeIndex],
+        distance: usize,
+        segment_lengths: &HashMap<NodeIndex, usize>,
+        overlaps: &[usize],
+        blocked: &HashSet<NodeIndex>,
+        graph_indices: &GFAGraphLookups,
+    ) -> Result<Vec<Vec<u8>>> {
+        eprintln!(
+            "[+]\tSearching around {} segment(s) up to {}bp away",
+            seeds.len(),
+            distance
+        );
+
+        let mut collected: BTreeSet<NodeIndex> = seeds.iter().copied().collect();
+        // the shortest distance to the far end of each oriented segment
+        let mut best: HashMap<(NodeIndex, Orientation), usize> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+
+        for seed in seeds {
+            for orientation in [Orientation::Forward, Orientation::Backward] {
+                best.insert((*seed, orientation), 0);
+                queue.push(Reverse((0, *seed, orientation)));
+            }
+        }
+
+        while let Some(Reverse((end, node, orientation))) = queue.pop() {
+            if best.get(&(node, orientation)).is_some_and(|b| *b < end) {
+                continue;
+            }
+            for (next, next_orientation, edge) in self.oriented_neighbours(node, orientation) {
+                if blocked.contains(&next) {
+                    continue;
+                }
+                let overlap = *overlaps
+                    .get(edge.index())
+                    .with_context(|| format!("No overlap for edge {:?}", edge))?;
+                let start = end.saturating_sub(overlap);
+                if start > distance {
+                    continue;
+                }
+                collected.insert(next);
+
+                let next_end = start
+                    + segment_lengths
+                        .get(&next)
+                        .with_context(|| format!("No sequence length for node {:?}", next))?;
+                let improved = best
+                    .get(&(next, next_orientation))
+                    .is_none_or(|b| next_end < *b);
+                if improved {
+                    best.insert((next, next_orientation), next_end);
+                    queue.push(Reverse((next_end, next, next_orientation)));
+                }
+            }
+        }
+
+        collected
+            .into_iter()
+            .map(|index| graph_indices.node_index_to_seg_id(index))
+            .collect()
+    }
+
     /// Simple wrapper of `Graph.node_count()` in petgraph.
     pub fn node_count(&self) -> usize {
         let gfa_graph = &self.0;
@@ -525,7 +635,10 @@ impl GFAdigraph {
             // there are no more nodes to delete.
             track_removed_nodes.push(removed_nodes.len());
             if track_removed_nodes.get(index).unwrap()
-                == track_removed_nodes.get(index - 1).unwrap_or(&0)
+                == index
+                    .checked_sub(1)
+                    .and_then(|i| track_removed_nodes.get(i))
+                    .unwrap_or(&0)
             {
                 break;
             }
@@ -545,6 +658,539 @@ impl GFAdigraph {
             .map(|e| graph_indices.node_index_to_seg_id(*e).unwrap())
             .collect::<Vec<_>>()
     }
+
+    /// The oriented segments which can precede `node`, when `node` is traversed
+    /// in `orientation`. The mirror of [`GFAdigraph::oriented_neighbours`].
+    pub fn oriented_parents(
+        &self,
+        node: NodeIndex,
+        orientation: Orientation,
+    ) -> Vec<(NodeIndex, Orientation, EdgeIndex)> {
+        self.oriented_neighbours(node, reverse_orientation(orientation))
+            .into_iter()
+            .map(|(n, o, e)| (n, reverse_orientation(o), e))
+            .collect()
+    }
+
+    /// The algorithm called in `gfatk bubbles`.
+    ///
+    /// Finds the superbubbles of the graph of oriented segments, using the algorithm of
+    /// Onodera et al. (2013) from each oriented segment in turn. A superbubble is a
+    /// subgraph with a single entry and a single exit, which is acyclic, has no tips,
+    /// and which no other segment links into or out of. Only bubbles with two or more
+    /// branches are returned, each once (and not also as its reverse), and the search
+    /// from each entry stops after `max_size` segments.
+    pub fn superbubbles(&self, max_size: usize) -> Vec<Bubble> {
+        let gfa_graph = &self.0;
+        let mut bubbles = Vec::new();
+        let mut found: HashSet<Vec<NodeIndex>> = HashSet::new();
+
+        for node in gfa_graph.node_indices() {
+            for orientation in [Orientation::Forward, Orientation::Backward] {
+                let bubble = match self.superbubble_from((node, orientation), max_size) {
+                    Some(b) => b,
+                    None => continue,
+                };
+                let mut key: Vec<NodeIndex> = bubble.interior.iter().map(|(n, _)| *n).collect();
+                key.extend([bubble.entry.0, bubble.exit.0]);
+                key.sort();
+                if found.insert(key) {
+                    bubbles.push(bubble);
+                }
+            }
+        }
+
+        bubbles
+    }
+
+    /// Search for a superbubble with entry `entry`.
+    fn superbubble_from(&self, entry: (NodeIndex, Orientation), max_size: usize) -> Option<Bubble> {
+        let children = |(n, o): (NodeIndex, Orientation)| {
+            self.oriented_neighbours(n, o)
+                .into_iter()
+                .map(|(n, o, _)| (n, o))
+                .collect::<Vec<_>>()
+        };
+        let parents = |(n, o): (NodeIndex, Orientation)| {
+            self.oriented_parents(n, o)
+                .into_iter()
+                .map(|(n, o, _)| (n, o))
+                .collect::<Vec<_>>()
+        };
+
+        let mut stack = vec![entry];
+        let mut visited = HashSet::new();
+        let mut seen = HashSet::from([entry]);
+        let mut branching = false;
+
+        while let Some(v) = stack.pop() {
+            visited.insert(v);
+            seen.remove(&v);
+            if visited.len() > max_size {
+                return None;
+            }
+
+            let next = children(v);
+            // a tip
+            if next.is_empty() {
+                return None;
+            }
+            if next.len() > 1 {
+                branching = true;
+            }
+            for u in next {
+                // a cycle back to the entry, in either orientation
+                if u.0 == entry.0 {
+                    return None;
+                }
+                seen.insert(u);
+                if parents(u).iter().all(|p| visited.contains(p)) {
+                    stack.push(u);
+                }
+            }
+
+            if stack.len() == 1 && seen.len() == 1 && seen.contains(&stack[0]) {
+                let exit = stack[0];
+                if !branching || children(exit).contains(&entry) {
+                    return None;
+                }
+                visited.remove(&entry);
+                let interior: Vec<(NodeIndex, Orientation)> = visited.into_iter().collect();
+                // a segment in both orientations is an inversion, not a bubble
+                let segments: HashSet<NodeIndex> = interior.iter().map(|(n, _)| *n).collect();
+                if segments.len() != interior.len() || segments.contains(&exit.0) {
+                    return None;
+                }
+                let simple = interior
+                    .iter()
+                    .all(|i| parents(*i) == vec![entry] && children(*i) == vec![exit]);
+                return Some(Bubble {
+                    entry,
+                    exit,
+                    interior,
+                    simple,
+                });
+            }
+        }
+
+        None
+    }
+
+    /// All the paths through a [`Bubble`], from its entry to its exit, up to `max_paths`.
+    ///
+    /// Each path is the interior oriented segments walked, and the edges walked
+    /// (of which there is one more than the segments).
+    #[allow(clippy::type_complexity)]
+    pub fn bubble_branches(
+        &self,
+        bubble: &Bubble,
+        max_paths: usize,
+    ) -> Vec<(Vec<(NodeIndex, Orientation)>, Vec<EdgeIndex>)> {
+        let mut branches = Vec::new();
+        // depth first, the bubble is acyclic
+        let mut stack = vec![(bubble.entry, Vec::new(), Vec::new())];
+
+        while let Some((v, path, edges)) = stack.pop() {
+            if branches.len() >= max_paths {
+                break;
+            }
+            for (n, o, e) in self.oriented_neighbours(v.0, v.1) {
+                let mut edges = edges.clone();
+                edges.push(e);
+                if (n, o) == bubble.exit {
+                    branches.push((path.clone(), edges));
+                } else if bubble.interior.contains(&(n, o)) {
+                    let mut path = path.clone();
+                    path.push((n, o));
+                    stack.push(((n, o), path, edges));
+                }
+            }
+        }
+
+        branches.truncate(max_paths);
+        branches
+    }
+
+    /// The bridges (links) and articulation points (segments) of the graph, whose
+    /// removal disconnects their component.
+    ///
+    /// Runs on an undirected side graph, with an edge for each link between two
+    /// segments, counting a link and its mirror once (so two segments joined in two
+    /// ways are not joined by a bridge). Segment `lengths` are indexed by node, and
+    /// the [`Cut`]s are in order of their component, as in
+    /// [`GFAdigraph::weakly_connected_components`].
+    pub fn cuts(&self, lengths: &[usize]) -> Vec<Cut> {
+        let graph = &self.0;
+        let n = graph.node_count();
+
+        // the side graph, with an edge for each link and its mirror
+        let mut adjacencies: HashMap<(usize, bool, usize, bool), EdgeIndex> = HashMap::new();
+        for edge in graph.edge_references() {
+            let (from, to) = (edge.source().index(), edge.target().index());
+            let (from_orient, to_orient) = (edge.weight().0, edge.weight().1);
+            if from == to {
+                continue;
+            }
+            let forward = (from, from_orient.is_reverse(), to, to_orient.is_reverse());
+            let mirror = (to, !to_orient.is_reverse(), from, !from_orient.is_reverse());
+            let key = forward.min(mirror);
+            adjacencies.entry(key).or_insert(edge.id());
+        }
+        let mut side_edges: Vec<(usize, usize, EdgeIndex)> = adjacencies
+            .into_iter()
+            .map(|((from, _, to, _), e)| (from, to, e))
+            .collect();
+        side_edges.sort_by_key(|(_, _, e)| *e);
+        let mut neighbours: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+        for (side_edge, (from, to, _)) in side_edges.iter().enumerate() {
+            neighbours[*from].push((*to, side_edge));
+            neighbours[*to].push((*from, side_edge));
+        }
+
+        let mut discovered = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        // the segments and bp below each node in the depth first search tree
+        let mut below = vec![(0, 0); n];
+        // the subtrees which are cut off if each node is removed
+        let mut cut_off: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+        let mut timer = 0;
+        let mut cuts = Vec::new();
+
+        for root in 0..n {
+            if discovered[root] != usize::MAX {
+                continue;
+            }
+            let mut bridges = Vec::new();
+            let mut visited = Vec::new();
+            // (node, the side edge to its parent, the next neighbour to visit)
+            let mut stack = vec![(root, usize::MAX, 0)];
+            discovered[root] = timer;
+            low[root] = timer;
+            timer += 1;
+
+            while let Some((node, parent_edge, next)) = stack.pop() {
+                if next == 0 {
+                    visited.push(node);
+                    below[node] = (1, lengths[node]);
+                }
+                if let Some(&(neighbour, side_edge)) = neighbours[node].get(next) {
+                    stack.push((node, parent_edge, next + 1));
+                    if side_edge == parent_edge {
+                        continue;
+                    }
+                    if discovered[neighbour] == usize::MAX {
+                        discovered[neighbour] = timer;
+                        low[neighbour] = timer;
+                        timer += 1;
+                        stack.push((neighbour, side_edge, 0));
+                    } else {
+                        low[node] = low[node].min(discovered[neighbour]);
+                    }
+                    continue;
+                }
+
+                // finished with this node, so update its parent
+                let Some(&(parent, _, _)) = stack.last() else {
+                    continue;
+                };
+                low[parent] = low[parent].min(low[node]);
+                below[parent].0 += below[node].0;
+                below[parent].1 += below[node].1;
+                if low[node] > discovered[parent] {
+                    bridges.push((side_edges[parent_edge].2, below[node]));
+                }
+                if low[node] >= discovered[parent] {
+                    cut_off[parent].push(below[node]);
+                }
+            }
+
+            // the rest of the component is on the other side of each cut
+            let total = below[root];
+            bridges.sort_by_key(|(edge, _)| *edge);
+            for (edge, piece) in bridges {
+                cuts.push(Cut {
+                    element: CutElement::Bridge(edge),
+                    pieces: vec![(total.0 - piece.0, total.1 - piece.1), piece],
+                });
+            }
+            visited.sort_unstable();
+            for node in visited {
+                let mut pieces = std::mem::take(&mut cut_off[node]);
+                let articulation = match node == root {
+                    true => pieces.len() > 1,
+                    false => !pieces.is_empty(),
+                };
+                if !articulation {
+                    continue;
+                }
+                if node != root {
+                    let rest = pieces
+                        .iter()
+                        .fold((total.0 - 1, total.1 - lengths[node]), |acc, p| {
+                            (acc.0 - p.0, acc.1 - p.1)
+                        });
+                    pieces.insert(0, rest);
+                }
+                cuts.push(Cut {
+                    element: CutElement::Articulation(NodeIndex::new(node)),
+                    pieces,
+                });
+            }
+        }
+
+        cuts
+    }
+
+    /// The `k` shortest walks from one oriented segment to another, in bp.
+    ///
+    /// Walks are orientation-consistent (see [`GFAdigraph::oriented_neighbours`]),
+    /// and do not visit an oriented segment twice. The length of a walk is the length
+    /// of its first segment, plus the length of each next segment less the overlap
+    /// of the link walked. Segment `lengths` are indexed by node and link `overlaps`
+    /// by edge. Returns the oriented segments of each walk and its length, shortest
+    /// first, using Yen's algorithm for the alternatives.
+    #[allow(clippy::type_complexity)]
+    pub fn shortest_walks(
+        &self,
+        from: (NodeIndex, Orientation),
+        to: (NodeIndex, Orientation),
+        lengths: &[usize],
+        overlaps: &[usize],
+        k: usize,
+    ) -> Vec<(Vec<(NodeIndex, Orientation)>, usize)> {
+        let no_nodes = HashSet::new();
+        let no_steps = HashSet::new();
+        let Some(shortest) = self.dijkstra_walk(from, to, lengths, overlaps, &no_nodes, &no_steps)
+        else {
+            return Vec::new();
+        };
+
+        let mut found = vec![shortest];
+        let mut candidates: Vec<Walk> = Vec::new();
+        while found.len() < k {
+            let (last, costs) = found[found.len() - 1].clone();
+            for i in 0..last.len().saturating_sub(1) {
+                let (spur, root) = (last[i], &last[..=i]);
+                // don't walk a step already taken from this root, or back into the root
+                let blocked_steps = found
+                    .iter()
+                    .filter(|(walk, _)| walk.len() > i + 1 && &walk[..=i] == root)
+                    .map(|(walk, _)| (walk[i], walk[i + 1]))
+                    .collect();
+                let blocked_nodes = root[..i].iter().copied().collect();
+
+                let Some((spur_walk, spur_costs)) =
+                    self.dijkstra_walk(spur, to, lengths, overlaps, &blocked_nodes, &blocked_steps)
+                else {
+                    continue;
+                };
+                let walk: Vec<_> = root[..i].iter().chain(&spur_walk).copied().collect();
+                let walk_costs: Vec<_> = costs[..i]
+                    .iter()
+                    .copied()
+                    .chain(spur_costs.iter().map(|c| c + costs[i]))
+                    .collect();
+                if !found.iter().chain(&candidates).any(|(w, _)| *w == walk) {
+                    candidates.push((walk, walk_costs));
+                }
+            }
+
+            let key = |(walk, costs): &Walk| {
+                let steps: Vec<_> = walk.iter().map(|(n, o)| (*n, o.is_reverse())).collect();
+                (costs[costs.len() - 1], steps)
+            };
+            let Some(next) = candidates
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, c)| key(c))
+                .map(|(i, _)| i)
+            else {
+                break;
+            };
+            found.push(candidates.swap_remove(next));
+        }
+
+        found
+            .into_iter()
+            .map(|(walk, costs)| {
+                let length = lengths[from.0.index()] + costs[costs.len() - 1];
+                (walk, length)
+            })
+            .collect()
+    }
+
+    /// Dijkstra's algorithm from one oriented segment to another, avoiding `blocked_nodes`
+    /// and `blocked_steps`. Returns the walk, and the cost to reach each step of it.
+    fn dijkstra_walk(
+        &self,
+        from: (NodeIndex, Orientation),
+        to: (NodeIndex, Orientation),
+        lengths: &[usize],
+        overlaps: &[usize],
+        blocked_nodes: &HashSet<(NodeIndex, Orientation)>,
+        blocked_steps: &HashSet<(OrientedNode, OrientedNode)>,
+    ) -> Option<Walk> {
+        let orientation = |reverse: bool| match reverse {
+            true => Orientation::Backward,
+            false => Orientation::Forward,
+        };
+        let mut costs: HashMap<(NodeIndex, Orientation), usize> = HashMap::from([(from, 0)]);
+        let mut previous: HashMap<(NodeIndex, Orientation), (NodeIndex, Orientation)> =
+            HashMap::new();
+        let mut heap = BinaryHeap::from([Reverse((0, from.0, from.1.is_reverse()))]);
+
+        while let Some(Reverse((cost, node, reverse))) = heap.pop() {
+            let current = (node, orientation(reverse));
+            if current == to {
+                let mut walk = vec![to];
+                while let Some(step) = previous.get(&walk[walk.len() - 1]) {
+                    walk.push(*step);
+                }
+                walk.reverse();
+                let walk_costs = walk.iter().map(|s| costs[s]).collect();
+                return Some((walk, walk_costs));
+            }
+            if costs.get(&current).is_some_and(|c| *c < cost) {
+                continue;
+            }
+            for (next, next_orient, edge) in self.oriented_neighbours(node, current.1) {
+                let step = (next, next_orient);
+                if blocked_nodes.contains(&step)
+                    || blocked_steps.contains(&(current, step))
+                    || step == from
+                {
+                    continue;
+                }
+                let next_cost = cost + lengths[next.index()].saturating_sub(overlaps[edge.index()]);
+                if costs.get(&step).is_none_or(|c| next_cost < *c) {
+                    costs.insert(step, next_cost);
+                    previous.insert(step, current);
+                    heap.push(Reverse((next_cost, next, next_orient.is_reverse())));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// The simple cycles of the graph: closed, orientation-consistent walks which
+    /// pass through each segment at most once.
+    ///
+    /// A cycle walked backwards (its reverse complement) is the same cycle, so each is
+    /// returned once, starting from its lowest indexed segment walked forwards. The
+    /// length of a cycle is the sum of the length of each segment less the overlap of
+    /// the link into it. Cycles longer than `max_length` are skipped, and the search
+    /// stops once `max_cycles` are found. Segment `lengths` are indexed by node and
+    /// link `overlaps` by edge.
+    pub fn simple_cycles(
+        &self,
+        lengths: &[usize],
+        overlaps: &[usize],
+        max_cycles: usize,
+        max_length: Option<usize>,
+    ) -> Vec<Cycle> {
+        let mut cycles = Vec::new();
+
+        for start in self.0.node_indices() {
+            let first = (start, Orientation::Forward);
+            let mut on_walk = HashSet::from([start]);
+            // each step of the walk, its neighbours, the next neighbour to try, and
+            // the edge and cost into it
+            let mut stack = vec![(first, self.oriented_neighbours(start, first.1), 0, None, 0)];
+
+            while let Some((step, neighbours, next, _, cost)) = stack.last_mut() {
+                let (step, cost) = (*step, *cost);
+                let Some(&(node, orientation, edge)) = neighbours.get(*next) else {
+                    on_walk.remove(&step.0);
+                    stack.pop();
+                    continue;
+                };
+                *next += 1;
+
+                let next_cost = cost + lengths[node.index()].saturating_sub(overlaps[edge.index()]);
+                if max_length.is_some_and(|max| next_cost > max) {
+                    continue;
+                }
+                if (node, orientation) == first {
+                    let mut edges: Vec<EdgeIndex> =
+                        stack.iter().skip(1).filter_map(|s| s.3).collect();
+                    edges.push(edge);
+                    cycles.push(Cycle {
+                        steps: stack.iter().map(|s| s.0).collect(),
+                        edges,
+                        length: next_cost,
+                    });
+                    if cycles.len() >= max_cycles {
+                        return cycles;
+                    }
+                    continue;
+                }
+                // only segments after the start, so each cycle is found from one place
+                if node.index() <= start.index() || on_walk.contains(&node) {
+                    continue;
+                }
+                on_walk.insert(node);
+                stack.push((
+                    (node, orientation),
+                    self.oriented_neighbours(node, orientation),
+                    0,
+                    Some(edge),
+                    next_cost,
+                ));
+            }
+        }
+
+        cycles
+    }
+}
+
+/// A segment, and the orientation it is walked in.
+type OrientedNode = (NodeIndex, Orientation);
+
+/// A walk of oriented segments, and the cost to reach each of them.
+type Walk = (Vec<OrientedNode>, Vec<usize>);
+
+/// A superbubble in a [`GFAdigraph`], from [`GFAdigraph::superbubbles`].
+#[derive(Clone, Debug)]
+pub struct Bubble {
+    /// The oriented segment the bubble is entered from.
+    pub entry: (NodeIndex, Orientation),
+    /// The oriented segment the bubble exits to.
+    pub exit: (NodeIndex, Orientation),
+    /// The oriented segments inside the bubble.
+    pub interior: Vec<(NodeIndex, Orientation)>,
+    /// Whether each interior segment joins the entry and exit directly.
+    pub simple: bool,
+}
+
+/// A simple cycle in a [`GFAdigraph`], from [`GFAdigraph::simple_cycles`].
+#[derive(Clone, Debug)]
+pub struct Cycle {
+    /// The oriented segments of the cycle, in the order walked.
+    pub steps: Vec<(NodeIndex, Orientation)>,
+    /// The link walked out of each step, the last closing the cycle.
+    pub edges: Vec<EdgeIndex>,
+    /// The length of the cycle in bp, with the overlaps removed.
+    pub length: usize,
+}
+
+/// A segment or link whose removal disconnects its component, from [`GFAdigraph::cuts`].
+#[derive(Clone, Debug)]
+pub struct Cut {
+    /// The bridge or articulation point.
+    pub element: CutElement,
+    /// The number of segments, and their total length, in each piece the component
+    /// falls into without the cut (not counting an articulation point itself).
+    pub pieces: Vec<(usize, usize)>,
+}
+
+/// The element of a [`Cut`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CutElement {
+    /// A link, as the first of it and its mirror in the GFA.
+    Bridge(EdgeIndex),
+    /// A segment.
+    Articulation(NodeIndex),
 }
 
 /// A function generic over certain types of `Directed` petgraph `Graph`s.
@@ -748,6 +1394,182 @@ pub fn segments_subgraph<T: OptFields + Clone>(
     }
 }
 
+/// The order of the lines of a GFA written by `gfatk`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Keep the order of the input.
+    Input,
+    /// Sort segments by name, with numbers compared as numbers.
+    Name,
+    /// Breadth first search from the longest segment of each component.
+    Bfs,
+    /// Topological order of the links, where the graph is acyclic.
+    Topological,
+}
+
+impl SortOrder {
+    /// Get the `--order` of the command line, or sort by name if there is none.
+    pub fn from_matches(matches: &clap::ArgMatches) -> Self {
+        match matches
+            .try_get_one::<String>("order")
+            .ok()
+            .flatten()
+            .map(|o| o.as_str())
+        {
+            Some("input") => SortOrder::Input,
+            Some("bfs") => SortOrder::Bfs,
+            Some("topological") => SortOrder::Topological,
+            _ => SortOrder::Name,
+        }
+    }
+}
+
+/// Sort the lines of a GFA into a canonical order, so that the same graph is
+/// always written the same way.
+///
+/// Segments are ordered by [`SortOrder`], ties and choices always broken by name
+/// (see [`natural_cmp`]). Links and containments follow the order of their segments,
+/// and paths are sorted by name.
+///
+/// [`SortOrder::Bfs`] visits each component from its longest segment, and the
+/// components by their longest segment. [`SortOrder::Topological`] follows the
+/// links (counting a link and its mirror once), and where there is a cycle,
+/// carries on from the remaining segment with fewest incoming links.
+pub fn sort_gfa<T: OptFields + Clone>(gfa: &GFA<Vec<u8>, T>, order: SortOrder) -> GFA<Vec<u8>, T> {
+    if order == SortOrder::Input {
+        return gfa.clone();
+    }
+
+    let mut names: Vec<&[u8]> = gfa.segments.iter().map(|s| s.name.as_slice()).collect();
+    names.sort_by(|a, b| natural_cmp(a, b));
+    names.dedup();
+    let by_name: HashMap<&[u8], usize> = names.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+
+    // the neighbours of each segment, as positions in `names`, in name order
+    let mut neighbours: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); names.len()];
+    let mut successors: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); names.len()];
+    let mut seen_links = HashSet::new();
+    for link in &gfa.links {
+        let (Some(&from), Some(&to)) = (
+            by_name.get(link.from_segment.as_slice()),
+            by_name.get(link.to_segment.as_slice()),
+        ) else {
+            continue;
+        };
+        neighbours[from].insert(to);
+        neighbours[to].insert(from);
+
+        let mirror = (
+            to,
+            reverse_orientation(link.to_orient),
+            from,
+            reverse_orientation(link.from_orient),
+        );
+        if from != to
+            && !seen_links.contains(&mirror)
+            && seen_links.insert((from, link.from_orient, to, link.to_orient))
+        {
+            successors[from].insert(to);
+        }
+    }
+
+    let ordered: Vec<usize> = match order {
+        SortOrder::Input | SortOrder::Name => (0..names.len()).collect(),
+        SortOrder::Bfs => {
+            let lengths: HashMap<&[u8], usize> = gfa
+                .segments
+                .iter()
+                .map(|s| (s.name.as_slice(), s.sequence.len()))
+                .collect();
+            // longest first, then by name
+            let mut starts: Vec<usize> = (0..names.len()).collect();
+            starts.sort_by_key(|i| Reverse(lengths[names[*i]]));
+
+            let mut visited = vec![false; names.len()];
+            let mut ordered = Vec::new();
+            for start in starts {
+                if visited[start] {
+                    continue;
+                }
+                visited[start] = true;
+                let mut queue = std::collections::VecDeque::from([start]);
+                while let Some(node) = queue.pop_front() {
+                    ordered.push(node);
+                    for next in &neighbours[node] {
+                        if !visited[*next] {
+                            visited[*next] = true;
+                            queue.push_back(*next);
+                        }
+                    }
+                }
+            }
+            ordered
+        }
+        SortOrder::Topological => {
+            let mut in_degree = vec![0; names.len()];
+            for next in successors.iter().flatten() {
+                in_degree[*next] += 1;
+            }
+            let mut placed = vec![false; names.len()];
+            let mut ready: BTreeSet<usize> =
+                (0..names.len()).filter(|i| in_degree[*i] == 0).collect();
+            let mut ordered = Vec::new();
+            while ordered.len() < names.len() {
+                let node = match ready.pop_first() {
+                    Some(node) => node,
+                    // a cycle, so break it
+                    None => (0..names.len())
+                        .filter(|i| !placed[*i])
+                        .min_by_key(|i| in_degree[*i])
+                        .expect("a segment is left to place"),
+                };
+                if placed[node] {
+                    continue;
+                }
+                placed[node] = true;
+                ordered.push(node);
+                for next in &successors[node] {
+                    in_degree[*next] -= 1;
+                    if in_degree[*next] == 0 && !placed[*next] {
+                        ready.insert(*next);
+                    }
+                }
+            }
+            ordered
+        }
+    };
+
+    let mut rank: HashMap<&[u8], usize> = HashMap::new();
+    for (position, index) in ordered.iter().enumerate() {
+        rank.insert(names[*index], position);
+    }
+    let rank_of = |name: &[u8]| rank.get(name).copied().unwrap_or(usize::MAX);
+
+    let mut sorted = gfa.clone();
+    sorted.segments.sort_by_key(|s| rank_of(&s.name));
+    sorted.links.sort_by_key(|l| {
+        (
+            rank_of(&l.from_segment),
+            l.from_orient.is_reverse(),
+            rank_of(&l.to_segment),
+            l.to_orient.is_reverse(),
+        )
+    });
+    sorted.containments.sort_by_key(|c| {
+        (
+            rank_of(&c.container_name),
+            c.container_orient.is_reverse(),
+            rank_of(&c.contained_name),
+            c.contained_orient.is_reverse(),
+        )
+    });
+    sorted
+        .paths
+        .sort_by(|a, b| natural_cmp(&a.path_name, &b.path_name));
+
+    sorted
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -778,7 +1600,7 @@ mod tests {
         //  5 <-----> 2 <-----> 4
         //
 
-        graph.extend_with_edges(&[
+        graph.extend_with_edges([
             (
                 node0,
                 node3,
@@ -984,7 +1806,7 @@ mod tests {
         ];
 
         // will be chosen
-        let both = vec![longest_path1, longest_path2];
+        let both = [longest_path1, longest_path2];
 
         let path = &paths.unwrap().0.iter().map(|(a, _)| *a).collect::<Vec<_>>();
 
@@ -1,17 +1,19 @@
 use ::gfa::writer::write_gfa;
 use ::gfa::{gfa::GFA, optfields::OptionalFields};
+use graph::{sort_gfa, SortOrder};
 
 /// A module where all the methods of GFA manipulations are defined.
+#[allow(clippy::module_inception)]
 pub mod gfa;
 /// A module where a GFA is coerced into a petgraph `Graph` structure, with associated methods.
 pub mod graph;
 
-/// Writes a GFA to a string.
+/// Writes a GFA to a string, in a canonical [`SortOrder`].
 ///
 /// Modified from function of the same name in gfa crate.
-pub fn gfa_string(gfa: &GFA<Vec<u8>, OptionalFields>) -> String {
+pub fn gfa_string(gfa: &GFA<Vec<u8>, OptionalFields>, order: SortOrder) -> String {
     let mut result = String::new();
-    write_gfa(gfa, &mut result);
+    write_gfa(&sort_gfa(gfa, order), &mut result);
     result
 }
 
@@ -9,6 +9,20 @@
 //! Almost all of the core functionality of `gfatk` resides in the [`gfatk::gfa`](./gfa/index.html)
 //! module. The other modules are entry points for the command line application.
 
+/// Find and pop bubbles and superbubbles in a GFA.
+pub mod bubbles;
+/// Split long segments in a GFA into pieces of bounded length.
+pub mod chop;
+/// Merge non-branching paths (unitigs) in a GFA into single segments.
+pub mod compact;
+/// List the bridges and articulation points of a GFA.
+pub mod cuts;
+/// List the simple cycles of a GFA.
+pub mod cycles;
+/// Find, and optionally collapse, duplicate segments of a GFA.
+pub mod dedup;
+/// Report the differences between two GFAs.
+pub mod diff;
 /// Make a DOT language representation of a GFA.
 pub mod dot;
 /// Extract a subgraph from a GFA.
@@ -19,6 +33,8 @@ pub mod extract_chloro;
 pub mod extract_mito;
 /// Print all the sequences in a GFA to fasta format.
 pub mod fasta;
+/// Filter low coverage segments and links from a GFA.
+pub mod filter;
 /// A module with all the methods to manipulate GFA's in.
 pub mod gfa;
 /// Coerce a GFA into a fasta, finding the longest path through the graph.
@@ -26,15 +42,35 @@ pub mod linear;
 /// Helper functions to load a GFA from a file, or read from STDIN.
 /// Modified from <https://github.com/chfi/rs-gfa-utils/blob/2065b001d107ee9f5d7abe04d65ab82193fc5904/src/commands.rs>
 pub mod load;
+/// Score subgraphs in a GFA by the containment of marker gene k-mers.
+pub mod markers;
+/// Merge several GFA files into one.
+pub mod merge;
+/// Canonicalise the links of a GFA, collapsing mirrored and duplicate links.
+pub mod normalize;
+/// Flip segments of a GFA so that as many links as possible are `+` to `+`.
+pub mod orient;
 /// Generate overlapping sequences between segments in a GFA.
 pub mod overlap;
+/// Partition the components of a GFA into mitochondria, plastid, nuclear and unassigned.
+pub mod partition;
 /// Extract a fasta given a path.
 pub mod path;
+/// Report the quadripartite structure of a putative plastid subgraph in a GFA.
+pub mod plastid_report;
+/// Organelle size and GC% presets used by `gfatk extract-mito` and `gfatk extract-chloro`.
+pub mod preset;
 /// Rename a GFA's segment ID's to a 1-indexed [`usize`].
 pub mod rename;
+/// Print a table of per-segment statistics of a GFA.
+pub mod segments;
+/// Find the shortest walks between two segments of a GFA.
+pub mod shortest_path;
 /// Generate statistics about the input GFA file.
 pub mod stats;
 /// Utility to trim a GFA of isolated nodes.
 pub mod trim;
+/// Unroll collapsed repeats in a GFA by their estimated copy number.
+pub mod unroll;
 /// Utility functions used throughout.
 pub mod utils;
@@ -1,12 +1,11 @@
 use std::path::PathBuf;
 
-use crate::gfa::gfa::GFAtk;
+use crate::gfa::gfa::{Circularity, GFAtk};
 use crate::gfa::graph::{segments_subgraph, GFAdigraph};
 use crate::load::{load_gfa, load_gfa_stdin};
 use crate::path::{parse_path, CLIOpt};
 use crate::utils::{self, GFAGraphLookups};
 use anyhow::{bail, Context, Result};
-use petgraph::algo::is_cyclic_directed;
 
 /// Force a linear representation of the GFA.
 ///
@@ -91,7 +90,7 @@ pub fn linear(matches: &clap::ArgMatches) -> Result<()> {
                 let (graph_indices_subgraph, subgraph) = subgraph_gfa.into_digraph()?;
 
                 // it can be useful to see here whether the subgraph is cyclic
-                let is_circular = is_cyclic_directed(&subgraph.0);
+                let is_circular = subgraph_gfa.circularity() == Circularity::Circular;
                 // check the node count here. If there's one segment, then we can just print the sequence.
                 // otherwise we go ahead and linearise the subgraph.
 
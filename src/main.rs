@@ -6,9 +6,11 @@ use std::path::PathBuf;
 use anyhow::Result;
 use clap::{crate_version, value_parser, Arg, ArgAction, Command};
 use gfatk::{
-    dot, extract, extract_chloro, extract_mito, fasta, linear, overlap, path, rename,
+    bubbles, chop, compact, cuts, cycles, dedup, diff, dot, extract, extract_chloro, extract_mito,
+    fasta, filter, linear, merge, normalize, orient, overlap, partition, path, plastid_report,
+    rename, segments, shortest_path,
     stats::{self, GenomeType},
-    trim,
+    trim, unroll,
 };
 
 fn main() -> Result<()> {
@@ -18,6 +20,14 @@ fn main() -> Result<()> {
         .arg_required_else_help(true)
         .author("Max Brown <mb39@sanger.ac.uk>")
         .about("Explore and linearise (plant organellar) GFA files.")
+        .arg(
+            Arg::new("order")
+                .long("order")
+                .global(true)
+                .default_value("name")
+                .value_parser(["name", "bfs", "topological", "input"])
+                .help("The order of segments (and their links) in output GFA's, and of components: by name (numeric-aware), breadth first from the longest segment, topological where possible, or as input."),
+        )
         .subcommand(
             Command::new("overlap")
                 .about("Extract overlaps from a GFA.")
@@ -59,6 +69,35 @@ fn main() -> Result<()> {
                         .default_value("3")
                         .value_parser(value_parser!(i32))
                         .help("Number of iterations to recursively search for connecting nodes."),
+                )
+                .arg(
+                    Arg::new("distance")
+                        .short('d')
+                        .long("distance")
+                        .value_parser(value_parser!(usize))
+                        .help("Instead of iterations, grow the subgraph to segments starting within this distance (bp) of the input segments, following segment orientations."),
+                )
+                .arg(
+                    Arg::new("stop-segments")
+                        .long("stop-segments")
+                        .value_delimiter(',')
+                        .value_parser(value_parser!(String))
+                        .requires("distance")
+                        .help("Segments which the distance search will never enter. Comma delimited, e.g. 1,2,3."),
+                )
+                .arg(
+                    Arg::new("min-coverage")
+                        .long("min-coverage")
+                        .value_parser(value_parser!(f32))
+                        .requires("distance")
+                        .help("The distance search will not enter segments below this coverage."),
+                )
+                .arg(
+                    Arg::new("max-coverage")
+                        .long("max-coverage")
+                        .value_parser(value_parser!(f32))
+                        .requires("distance")
+                        .help("The distance search will not enter segments above this coverage."),
                 ),
         )
         .subcommand(
@@ -113,12 +152,47 @@ fn main() -> Result<()> {
                         .value_parser(value_parser!(PathBuf))
                         .help("Input GFA file.")
                 )
+                .arg(
+                    Arg::new("normalize-links")
+                        .short('n')
+                        .long("normalize-links")
+                        .action(ArgAction::SetTrue)
+                        .help("Count a link and its mirror (and duplicate links) once, as in `gfatk normalize`."),
+                )
                 .arg(
                     Arg::new("tabular")
                         .short('t')
                         .long("tabular")
                         .action(ArgAction::SetTrue)
-                        .help("Output tabular stats.")
+                        .help("Output tabular stats. Equivalent to --format tsv.")
+                )
+                .arg(
+                    Arg::new("format")
+                        .short('f')
+                        .long("format")
+                        .default_value("text")
+                        .value_parser(["text", "tsv", "json", "jsonl"])
+                        .help("Output format. json and jsonl (JSON Lines) include whether each subgraph would be extracted as mitochondria or plastid."),
+                )
+                .arg(
+                    Arg::new("mito-preset")
+                        .long("mito-preset")
+                        .default_value("plant-mito")
+                        .value_parser(value_parser!(String))
+                        .help("Size and GC% preset the subgraphs are called against as mitochondria, in JSON output."),
+                )
+                .arg(
+                    Arg::new("plastid-preset")
+                        .long("plastid-preset")
+                        .default_value("plant-plastid")
+                        .value_parser(value_parser!(String))
+                        .help("Size and GC% preset the subgraphs are called against as plastid, in JSON output."),
+                )
+                .arg(
+                    Arg::new("preset-file")
+                        .long("preset-file")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("TOML file of presets, adding to or overriding the built in presets."),
                 ),
         )
         .subcommand(
@@ -134,32 +208,45 @@ fn main() -> Result<()> {
                 .arg(
                     Arg::new("size-lower")
                         .long("size-lower")
-                        // 200,000 default
-                        .default_value("200000")
                         .value_parser(value_parser!(usize))
-                        .help("Minimum size (bp) of expected mitochondria."),
+                        .help("Minimum size (bp) of expected mitochondria. Overrides the preset."),
                 )
                 .arg(
                     Arg::new("size-upper")
                         .long("size-upper")
-                        // 1 million is high enough for most species?
-                        .default_value("1000000")
                         .value_parser(value_parser!(usize))
-                        .help("Maximum size (bp) of expected mitochondria."),
+                        .help("Maximum size (bp) of expected mitochondria. Overrides the preset."),
                 )
                 .arg(
                     Arg::new("gc-lower")
                         .long("gc-lower")
-                        .default_value("0.42")
                         .value_parser(value_parser!(f32))
-                        .help("Minimum GC% of expected mitochondria."),
+                        .help("Minimum GC% of expected mitochondria. Overrides the preset."),
                 )
                 .arg(
                     Arg::new("gc-upper")
                         .long("gc-upper")
-                        .default_value("0.50")
                         .value_parser(value_parser!(f32))
-                        .help("Maximum GC% of expected mitochondria."),
+                        .help("Maximum GC% of expected mitochondria. Overrides the preset."),
+                )
+                .arg(
+                    Arg::new("size-slack")
+                        .long("size-slack")
+                        .value_parser(value_parser!(usize))
+                        .help("Slack (bp) added to the maximum size, to allow for overlaps between segments. Overrides the preset."),
+                )
+                .arg(
+                    Arg::new("preset")
+                        .short('p')
+                        .long("preset")
+                        .value_parser(value_parser!(String))
+                        .help("Size and GC% preset for the expected mitochondria. One of plant-mito, vertebrate-mito, invertebrate-mito, fungi-mito, algae-mito, or a preset from --preset-file. [default: plant-mito]"),
+                )
+                .arg(
+                    Arg::new("preset-file")
+                        .long("preset-file")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("TOML file of presets, adding to or overriding the built in presets."),
                 )
                 .arg(
                     Arg::new("tabular")
@@ -168,6 +255,38 @@ fn main() -> Result<()> {
                         .action(ArgAction::SetTrue)
                         .help("Output tabular stats.")
                 )
+                .arg(
+                    Arg::new("markers")
+                        .short('m')
+                        .long("markers")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Fasta file of marker genes. Subgraphs are then scored by marker k-mer containment as well as GC% and size.")
+                )
+                .arg(
+                    Arg::new("kmer-size")
+                        .short('k')
+                        .long("kmer-size")
+                        .default_value("21")
+                        .value_parser(value_parser!(usize))
+                        .requires("markers")
+                        .help("K-mer size used to match marker genes."),
+                )
+                .arg(
+                    Arg::new("min-containment")
+                        .long("min-containment")
+                        .default_value("0.5")
+                        .value_parser(value_parser!(f32))
+                        .requires("markers")
+                        .help("Minimum fraction of a marker's k-mers found in a subgraph for the marker to count as present."),
+                )
+                .arg(
+                    Arg::new("min-score")
+                        .long("min-score")
+                        .default_value("0.75")
+                        .value_parser(value_parser!(f32))
+                        .requires("markers")
+                        .help("Minimum score (0-1) of a subgraph to be extracted as mitochondria. GC% and size in range each score 0.25, any marker present 0.5."),
+                )
                 ,
         )
         .subcommand(
@@ -183,30 +302,45 @@ fn main() -> Result<()> {
                 .arg(
                     Arg::new("size-lower")
                         .long("size-lower")
-                        .default_value("126000")
                         .value_parser(value_parser!(usize))
-                        .help("Minimum size (bp) of expected plastid."),
+                        .help("Minimum size (bp) of expected plastid. Overrides the preset."),
                 )
                 .arg(
                     Arg::new("size-upper")
                         .long("size-upper")
-                        .default_value("180000")
                         .value_parser(value_parser!(usize))
-                        .help("Maximum size (bp) of expected plastid."),
+                        .help("Maximum size (bp) of expected plastid. Overrides the preset."),
                 )
                 .arg(
                     Arg::new("gc-lower")
                         .long("gc-lower")
-                        .default_value("0.35")
                         .value_parser(value_parser!(f32))
-                        .help("Minimum GC% of expected plastid."),
+                        .help("Minimum GC% of expected plastid. Overrides the preset."),
                 )
                 .arg(
                     Arg::new("gc-upper")
                         .long("gc-upper")
-                        .default_value("0.39")
                         .value_parser(value_parser!(f32))
-                        .help("Maximum GC% of expected plastid."),
+                        .help("Maximum GC% of expected plastid. Overrides the preset."),
+                )
+                .arg(
+                    Arg::new("size-slack")
+                        .long("size-slack")
+                        .value_parser(value_parser!(usize))
+                        .help("Slack (bp) added to the maximum size, to allow for overlaps between segments. Overrides the preset."),
+                )
+                .arg(
+                    Arg::new("preset")
+                        .short('p')
+                        .long("preset")
+                        .value_parser(value_parser!(String))
+                        .help("Size and GC% preset for the expected plastid. One of plant-plastid, algae-plastid, or a preset from --preset-file. [default: plant-plastid]"),
+                )
+                .arg(
+                    Arg::new("preset-file")
+                        .long("preset-file")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("TOML file of presets, adding to or overriding the built in presets."),
                 )
                 .arg(
                     Arg::new("tabular")
@@ -214,6 +348,179 @@ fn main() -> Result<()> {
                         .long("tabular")
                         .action(ArgAction::SetTrue)
                         .help("Output tabular stats.")
+                )
+                .arg(
+                    Arg::new("markers")
+                        .short('m')
+                        .long("markers")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Fasta file of marker genes. Subgraphs are then scored by marker k-mer containment as well as GC% and size.")
+                )
+                .arg(
+                    Arg::new("kmer-size")
+                        .short('k')
+                        .long("kmer-size")
+                        .default_value("21")
+                        .value_parser(value_parser!(usize))
+                        .requires("markers")
+                        .help("K-mer size used to match marker genes."),
+                )
+                .arg(
+                    Arg::new("min-containment")
+                        .long("min-containment")
+                        .default_value("0.5")
+                        .value_parser(value_parser!(f32))
+                        .requires("markers")
+                        .help("Minimum fraction of a marker's k-mers found in a subgraph for the marker to count as present."),
+                )
+                .arg(
+                    Arg::new("min-score")
+                        .long("min-score")
+                        .default_value("0.75")
+                        .value_parser(value_parser!(f32))
+                        .requires("markers")
+                        .help("Minimum score (0-1) of a subgraph to be extracted as plastid. GC% and size in range each score 0.25, any marker present 0.5."),
+                ),
+        )
+        .subcommand(
+            Command::new("plastid-report")
+                .about(
+                    "Report the LSC/SSC/IR structure of the plastid in a GFA.",
+                )
+                .arg(
+                    Arg::new("GFA")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Input GFA file.")
+                )
+                .arg(
+                    Arg::new("size-lower")
+                        .long("size-lower")
+                        .value_parser(value_parser!(usize))
+                        .help("Minimum size (bp) of expected plastid. Overrides the preset."),
+                )
+                .arg(
+                    Arg::new("size-upper")
+                        .long("size-upper")
+                        .value_parser(value_parser!(usize))
+                        .help("Maximum size (bp) of expected plastid. Overrides the preset."),
+                )
+                .arg(
+                    Arg::new("gc-lower")
+                        .long("gc-lower")
+                        .value_parser(value_parser!(f32))
+                        .help("Minimum GC% of expected plastid. Overrides the preset."),
+                )
+                .arg(
+                    Arg::new("gc-upper")
+                        .long("gc-upper")
+                        .value_parser(value_parser!(f32))
+                        .help("Maximum GC% of expected plastid. Overrides the preset."),
+                )
+                .arg(
+                    Arg::new("size-slack")
+                        .long("size-slack")
+                        .value_parser(value_parser!(usize))
+                        .help("Slack (bp) added to the maximum size, to allow for overlaps between segments. Overrides the preset."),
+                )
+                .arg(
+                    Arg::new("preset")
+                        .short('p')
+                        .long("preset")
+                        .value_parser(value_parser!(String))
+                        .help("Size and GC% preset for the expected plastid. One of plant-plastid, algae-plastid, or a preset from --preset-file. [default: plant-plastid]"),
+                )
+                .arg(
+                    Arg::new("preset-file")
+                        .long("preset-file")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("TOML file of presets, adding to or overriding the built in presets."),
+                ),
+        )
+        .subcommand(
+            Command::new("partition")
+                .about(
+                    "Partition a GFA into mitochondria, plastid, nuclear and unassigned components.",
+                )
+                .arg(
+                    Arg::new("GFA")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Input GFA file.")
+                )
+                .arg(
+                    Arg::new("output-prefix")
+                        .short('o')
+                        .long("output-prefix")
+                        .default_value("partition")
+                        .value_parser(value_parser!(String))
+                        .help("Prefix of the output GFA files, one per partition: <prefix>.mito.gfa, <prefix>.plastid.gfa, <prefix>.nuclear.gfa and <prefix>.unassigned.gfa."),
+                )
+                .arg(
+                    Arg::new("mito-preset")
+                        .long("mito-preset")
+                        .default_value("plant-mito")
+                        .value_parser(value_parser!(String))
+                        .help("Size and GC% preset for the mitochondria. One of plant-mito, vertebrate-mito, invertebrate-mito, fungi-mito, algae-mito, or a preset from --preset-file."),
+                )
+                .arg(
+                    Arg::new("plastid-preset")
+                        .long("plastid-preset")
+                        .default_value("plant-plastid")
+                        .value_parser(value_parser!(String))
+                        .help("Size and GC% preset for the plastid. One of plant-plastid, algae-plastid, or a preset from --preset-file."),
+                )
+                .arg(
+                    Arg::new("preset-file")
+                        .long("preset-file")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("TOML file of presets, adding to or overriding the built in presets."),
+                )
+                .arg(
+                    Arg::new("mito-markers")
+                        .long("mito-markers")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Fasta file of mitochondrial marker genes, used to score components as mitochondria."),
+                )
+                .arg(
+                    Arg::new("plastid-markers")
+                        .long("plastid-markers")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Fasta file of plastid marker genes, used to score components as plastid."),
+                )
+                .arg(
+                    Arg::new("kmer-size")
+                        .short('k')
+                        .long("kmer-size")
+                        .default_value("21")
+                        .value_parser(value_parser!(usize))
+                        .help("K-mer size used to match marker genes."),
+                )
+                .arg(
+                    Arg::new("min-containment")
+                        .long("min-containment")
+                        .default_value("0.5")
+                        .value_parser(value_parser!(f32))
+                        .help("Minimum fraction of a marker's k-mers found in a component for the marker to count as present."),
+                )
+                .arg(
+                    Arg::new("min-score")
+                        .long("min-score")
+                        .default_value("0.75")
+                        .value_parser(value_parser!(f32))
+                        .help("Minimum score (0-1) of a component to be claimed by an organelle with markers. GC% and size in range each score 0.25, any marker present 0.5."),
+                )
+                .arg(
+                    Arg::new("prefer")
+                        .long("prefer")
+                        .default_value("none")
+                        .value_parser(["mito", "plastid", "none"])
+                        .help("Organelle given a component claimed by both with equal scores. With none, the component is left unassigned."),
+                )
+                .arg(
+                    Arg::new("organelle-coverage")
+                        .long("organelle-coverage")
+                        .default_value("3.0")
+                        .value_parser(value_parser!(f32))
+                        .help("Components claimed by neither organelle, with coverage at least this many times the nuclear coverage, are left unassigned rather than nuclear."),
                 ),
         )
         .subcommand(
@@ -223,6 +530,13 @@ fn main() -> Result<()> {
                     Arg::new("GFA")
                         .value_parser(value_parser!(PathBuf))
                         .help("Input GFA file.")
+                )
+                .arg(
+                    Arg::new("normalize-links")
+                        .short('n')
+                        .long("normalize-links")
+                        .action(ArgAction::SetTrue)
+                        .help("Count a link and its mirror (and duplicate links) once, as in `gfatk normalize`."),
                 ),
         )
         .subcommand(
@@ -232,6 +546,33 @@ fn main() -> Result<()> {
                     Arg::new("GFA")
                         .value_parser(value_parser!(PathBuf))
                         .help("Input GFA file.")
+                )
+                .arg(
+                    Arg::new("tips")
+                        .long("tips")
+                        .action(ArgAction::SetTrue)
+                        .help("Only clip dead-end branches which are short, or low coverage relative to the segments they join."),
+                )
+                .arg(
+                    Arg::new("max-tip-length")
+                        .long("max-tip-length")
+                        .default_value("1000")
+                        .value_parser(value_parser!(usize))
+                        .help("With --tips, clip tips shorter than this (bp). Zero turns this off."),
+                )
+                .arg(
+                    Arg::new("tip-coverage")
+                        .long("tip-coverage")
+                        .default_value("0.1")
+                        .value_parser(value_parser!(f32))
+                        .help("With --tips, clip tips with coverage below this fraction of the segments they join. Zero turns this off."),
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .action(ArgAction::SetTrue)
+                        .requires("tips")
+                        .help("With --tips, print a TSV of the tips which would be clipped, instead of the GFA."),
                 ),
         )
         .subcommand(
@@ -275,6 +616,382 @@ fn main() -> Result<()> {
                         .help("Input GFA file.")
                 )
         )
+        .subcommand(
+            Command::new("segments")
+                .about("Print a table of per-segment statistics of a GFA.")
+                .arg(
+                    Arg::new("GFA")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Input GFA file.")
+                )
+                .arg(
+                    Arg::new("min-length")
+                        .long("min-length")
+                        .value_parser(value_parser!(usize))
+                        .help("Only output segments at least this long."),
+                )
+                .arg(
+                    Arg::new("max-length")
+                        .long("max-length")
+                        .value_parser(value_parser!(usize))
+                        .help("Only output segments at most this long."),
+                )
+                .arg(
+                    Arg::new("min-coverage")
+                        .long("min-coverage")
+                        .value_parser(value_parser!(f32))
+                        .help("Only output segments with at least this coverage."),
+                )
+                .arg(
+                    Arg::new("max-coverage")
+                        .long("max-coverage")
+                        .value_parser(value_parser!(f32))
+                        .help("Only output segments with at most this coverage."),
+                )
+                .arg(
+                    Arg::new("component")
+                        .short('c')
+                        .long("component")
+                        .value_parser(value_parser!(usize))
+                        .help("Only output segments in this component (as indexed in the output)."),
+                )
+                .arg(
+                    Arg::new("tips")
+                        .long("tips")
+                        .action(ArgAction::SetTrue)
+                        .help("Only output tips (segments with links on one end only)."),
+                )
+                .arg(
+                    Arg::new("sort")
+                        .short('s')
+                        .long("sort")
+                        .value_parser(["name", "length", "gc", "coverage", "copy-number", "component"])
+                        .help("Sort the output on this column. Otherwise segments are in GFA order."),
+                )
+                .arg(
+                    Arg::new("descending")
+                        .long("descending")
+                        .action(ArgAction::SetTrue)
+                        .help("Reverse the order of the output."),
+                )
+        )
+        .subcommand(
+            Command::new("compact")
+                .about("Merge non-branching paths in a GFA into single segments.")
+                .arg(
+                    Arg::new("GFA")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Input GFA file.")
+                )
+                .arg(
+                    Arg::new("mapping")
+                        .short('m')
+                        .long("mapping")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Write a TSV mapping each new segment to the original oriented segments to this file."),
+                )
+        )
+        .subcommand(
+            Command::new("bubbles")
+                .about("Find simple bubbles and superbubbles in a GFA, and optionally pop them.")
+                .arg(
+                    Arg::new("GFA")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Input GFA file.")
+                )
+                .arg(
+                    Arg::new("max-bubble-size")
+                        .long("max-bubble-size")
+                        .default_value("100")
+                        .value_parser(value_parser!(usize))
+                        .help("Maximum number of oriented segments searched from each bubble entry."),
+                )
+                .arg(
+                    Arg::new("max-branches")
+                        .long("max-branches")
+                        .default_value("16")
+                        .value_parser(value_parser!(usize))
+                        .help("Maximum number of branches reported for each bubble."),
+                )
+                .arg(
+                    Arg::new("pop")
+                        .long("pop")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Pop the bubbles, keeping the highest coverage branch, and write the GFA to this file."),
+                )
+        )
+        .subcommand(
+            Command::new("filter")
+                .about("Remove low coverage segments and links from a GFA.")
+                .arg(
+                    Arg::new("GFA")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Input GFA file.")
+                )
+                .arg(
+                    Arg::new("min-coverage")
+                        .long("min-coverage")
+                        .value_parser(value_parser!(f32))
+                        .help("Remove segments with coverage (ll) below this."),
+                )
+                .arg(
+                    Arg::new("min-relative-coverage")
+                        .long("min-relative-coverage")
+                        .value_parser(value_parser!(f32))
+                        .help("Remove segments with coverage below this fraction of the length weighted median segment coverage of their component."),
+                )
+                .arg(
+                    Arg::new("min-link-coverage")
+                        .long("min-link-coverage")
+                        .value_parser(value_parser!(i64))
+                        .help("Remove links with coverage (ec) below this."),
+                )
+                .arg(
+                    Arg::new("min-relative-link-coverage")
+                        .long("min-relative-link-coverage")
+                        .value_parser(value_parser!(f32))
+                        .help("Remove links with coverage below this fraction of the median link coverage of their component."),
+                )
+                .arg(
+                    Arg::new("report")
+                        .short('r')
+                        .long("report")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Write a TSV of the removed segments and links to this file."),
+                )
+        )
+        .subcommand(
+            Command::new("normalize")
+                .about("Canonicalise the links of a GFA, collapsing mirrored and duplicate links.")
+                .arg(
+                    Arg::new("GFA")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Input GFA file.")
+                )
+                .arg(
+                    Arg::new("mirrors")
+                        .short('m')
+                        .long("mirrors")
+                        .action(ArgAction::SetTrue)
+                        .help("Write every link with its mirror (as MBG does), adding any missing mirrors."),
+                )
+                .arg(
+                    Arg::new("report")
+                        .short('r')
+                        .long("report")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Write a TSV of duplicate, coverage mismatched and unmirrored links to this file."),
+                )
+        )
+        .subcommand(
+            Command::new("chop")
+                .about("Split long segments in a GFA into pieces no longer than a maximum length.")
+                .arg(
+                    Arg::new("GFA")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Input GFA file.")
+                )
+                .arg(
+                    Arg::new("max-len")
+                        .short('l')
+                        .long("max-len")
+                        .required(true)
+                        .value_parser(value_parser!(usize))
+                        .help("The maximum length (bp) of a segment."),
+                )
+                .arg(
+                    Arg::new("mapping")
+                        .short('m')
+                        .long("mapping")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Write a TSV mapping each original segment to its pieces to this file."),
+                )
+        )
+        .subcommand(
+            Command::new("merge")
+                .about("Merge several GFA files into one, renaming colliding segments.")
+                .arg(
+                    Arg::new("GFA")
+                        .value_parser(value_parser!(PathBuf))
+                        .num_args(1..)
+                        .required(true)
+                        .help("Input GFA files.")
+                )
+                .arg(
+                    Arg::new("rename")
+                        .short('r')
+                        .long("rename")
+                        .default_value("prefix")
+                        .value_parser(["prefix", "renumber"])
+                        .help("Rename segments colliding with an earlier input by prefixing the file stem, or with the next unused integer."),
+                )
+                .arg(
+                    Arg::new("dedup")
+                        .short('d')
+                        .long("dedup")
+                        .default_value("none")
+                        .value_parser(["none", "sequence", "reverse-complement"])
+                        .help("Merge segments with the same sequence (or reverse complement) as a segment of an earlier input."),
+                )
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Report the differences between two GFA files.")
+                .arg(
+                    Arg::new("GFA-A")
+                        .value_parser(value_parser!(PathBuf))
+                        .required(true)
+                        .help("The first input GFA file."),
+                )
+                .arg(
+                    Arg::new("GFA-B")
+                        .value_parser(value_parser!(PathBuf))
+                        .required(true)
+                        .help("The second input GFA file."),
+                )
+                .arg(
+                    Arg::new("coverage-threshold")
+                        .short('c')
+                        .long("coverage-threshold")
+                        .default_value("0.2")
+                        .value_parser(value_parser!(f32))
+                        .help("Report segment and link coverages changed by more than this fraction of the first GFA."),
+                )
+        )
+        .subcommand(
+            Command::new("unroll")
+                .about("Unroll collapsed repeats in a GFA by their estimated copy number.")
+                .arg(
+                    Arg::new("GFA")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Input GFA file."),
+                )
+                .arg(
+                    Arg::new("max-copies")
+                        .short('c')
+                        .long("max-copies")
+                        .default_value("4")
+                        .value_parser(value_parser!(usize))
+                        .help("Leave segments with more copies than this as they are."),
+                )
+                .arg(
+                    Arg::new("tolerance")
+                        .short('t')
+                        .long("tolerance")
+                        .default_value("0.2")
+                        .value_parser(value_parser!(f32))
+                        .help("How much (as a fraction) the coverages of the segments either side of a repeat must differ to pair them."),
+                )
+                .arg(
+                    Arg::new("report")
+                        .short('r')
+                        .long("report")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Write a TSV of each repeat, and whether it was unrolled, to this file."),
+                )
+        )
+        .subcommand(
+            Command::new("orient")
+                .about("Flip segments of a GFA so that as many links as possible are + to +.")
+                .arg(
+                    Arg::new("GFA")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Input GFA file."),
+                )
+                .arg(
+                    Arg::new("report")
+                        .short('r')
+                        .long("report")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Write a TSV of the segments flipped in each component to this file."),
+                )
+        )
+        .subcommand(
+            Command::new("cuts")
+                .about("List the bridge links and articulation segments of each component of a GFA.")
+                .arg(
+                    Arg::new("GFA")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Input GFA file."),
+                )
+        )
+        .subcommand(
+            Command::new("shortest-path")
+                .about("Find the shortest walk (in bp) between two oriented segments of a GFA.")
+                .arg(
+                    Arg::new("FROM")
+                        .index(1)
+                        .required(true)
+                        .help("The segment to walk from, with its orientation. E.g. 12+"),
+                )
+                .arg(
+                    Arg::new("TO")
+                        .index(2)
+                        .required(true)
+                        .help("The segment to walk to, with its orientation. E.g. 4-"),
+                )
+                .arg(
+                    Arg::new("GFA")
+                        .index(3)
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Input GFA file."),
+                )
+                .arg(
+                    Arg::new("k")
+                        .short('k')
+                        .long("k")
+                        .default_value("1")
+                        .value_parser(value_parser!(usize))
+                        .help("Output the k shortest walks."),
+                )
+        )
+        .subcommand(
+            Command::new("cycles")
+                .about("List the simple cycles (candidate circular molecules) of a GFA.")
+                .arg(
+                    Arg::new("GFA")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Input GFA file."),
+                )
+                .arg(
+                    Arg::new("max-cycles")
+                        .short('n')
+                        .long("max-cycles")
+                        .default_value("1000")
+                        .value_parser(value_parser!(usize))
+                        .help("Stop after finding this many cycles."),
+                )
+                .arg(
+                    Arg::new("max-length")
+                        .short('l')
+                        .long("max-length")
+                        .value_parser(value_parser!(usize))
+                        .help("Skip cycles longer than this (bp)."),
+                )
+        )
+        .subcommand(
+            Command::new("dedup")
+                .about("Find segments with identical, or reverse complement identical, sequences.")
+                .arg(
+                    Arg::new("GFA")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Input GFA file."),
+                )
+                .arg(
+                    Arg::new("same-strand")
+                        .short('s')
+                        .long("same-strand")
+                        .action(ArgAction::SetTrue)
+                        .help("Only find identical sequences, not reverse complements."),
+                )
+                .arg(
+                    Arg::new("collapse")
+                        .short('c')
+                        .long("collapse")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Collapse each group into one segment, rewiring links and paths, and write the GFA to this file."),
+                )
+        )
         .get_matches();
 
     match matches.subcommand() {
@@ -299,6 +1016,12 @@ fn main() -> Result<()> {
         Some(("extract-chloro", matches)) => {
             extract_chloro::extract_chloro(matches, GenomeType::Chloroplast)?;
         }
+        Some(("plastid-report", matches)) => {
+            plastid_report::plastid_report(matches)?;
+        }
+        Some(("partition", matches)) => {
+            partition::partition(matches)?;
+        }
         Some(("dot", matches)) => {
             dot::dot(matches)?;
         }
@@ -311,6 +1034,48 @@ fn main() -> Result<()> {
         Some(("rename", matches)) => {
             rename::rename_gfa(matches)?;
         }
+        Some(("segments", matches)) => {
+            segments::segments(matches)?;
+        }
+        Some(("compact", matches)) => {
+            compact::compact(matches)?;
+        }
+        Some(("bubbles", matches)) => {
+            bubbles::bubbles(matches)?;
+        }
+        Some(("filter", matches)) => {
+            filter::filter(matches)?;
+        }
+        Some(("normalize", matches)) => {
+            normalize::normalize(matches)?;
+        }
+        Some(("chop", matches)) => {
+            chop::chop(matches)?;
+        }
+        Some(("merge", matches)) => {
+            merge::merge(matches)?;
+        }
+        Some(("diff", matches)) => {
+            diff::diff(matches)?;
+        }
+        Some(("unroll", matches)) => {
+            unroll::unroll(matches)?;
+        }
+        Some(("orient", matches)) => {
+            orient::orient(matches)?;
+        }
+        Some(("cuts", matches)) => {
+            cuts::cuts(matches)?;
+        }
+        Some(("shortest-path", matches)) => {
+            shortest_path::shortest_path(matches)?;
+        }
+        Some(("cycles", matches)) => {
+            cycles::cycles(matches)?;
+        }
+        Some(("dedup", matches)) => {
+            dedup::dedup(matches)?;
+        }
         _ => {
             eprintln!("Subcommand invalid, run with '--help' for subcommand options. Exiting.");
             std::process::exit(1);
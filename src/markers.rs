@@ -0,0 +1,117 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::utils::reverse_complement;
+use anyhow::{bail, Context, Result};
+
+/// A set of marker gene sequences (e.g. cox1, rbcL, matK), stored as
+/// canonical k-mers.
+pub struct Markers {
+    /// The k-mer size.
+    pub k: usize,
+    /// The name of each marker, and its set of canonical k-mers.
+    pub markers: Vec<(String, HashSet<Vec<u8>>)>,
+}
+
+impl Markers {
+    /// Read marker sequences from a fasta file, and split them into canonical k-mers.
+    pub fn from_fasta<P: AsRef<Path>>(path: P, k: usize) -> Result<Self> {
+        if k == 0 {
+            bail!("The k-mer size must be greater than zero.");
+        }
+        let file = fs::File::open(path.as_ref()).with_context(|| {
+            format!(
+                "Could not open marker fasta: {:?}",
+                path.as_ref().as_os_str()
+            )
+        })?;
+
+        let mut records: Vec<(String, Vec<u8>)> = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            let line = line.trim();
+            if let Some(header) = line.strip_prefix('>') {
+                let name = header.split_whitespace().next().unwrap_or("").to_string();
+                records.push((name, Vec::new()));
+            } else if !line.is_empty() {
+                match records.last_mut() {
+                    Some((_, seq)) => seq.extend(line.as_bytes()),
+                    None => bail!("Marker fasta should start with a '>' header line."),
+                }
+            }
+        }
+
+        let mut markers = Vec::new();
+        for (name, seq) in records {
+            let kmers = canonical_kmers(&seq, k);
+            if kmers.is_empty() {
+                eprintln!(
+                    "[-]\tMarker {} is shorter than the k-mer size ({}). Skipping.",
+                    name, k
+                );
+                continue;
+            }
+            markers.push((name, kmers));
+        }
+
+        if markers.is_empty() {
+            bail!("No usable marker sequences found.");
+        }
+
+        Ok(Self { k, markers })
+    }
+
+    /// The containment of each marker in a set of sequences.
+    ///
+    /// That is, the fraction of each marker's k-mers which occur in any of
+    /// the sequences (in either orientation).
+    pub fn containment<'a, I>(&self, sequences: I) -> Vec<f32>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        // only the marker k-mers need to be kept track of
+        let mut lookup: HashMap<&[u8], bool> = HashMap::new();
+        for (_, kmers) in &self.markers {
+            for kmer in kmers {
+                lookup.insert(kmer, false);
+            }
+        }
+
+        for seq in sequences {
+            for kmer in canonical_kmers(seq, self.k) {
+                if let Some(found) = lookup.get_mut(kmer.as_slice()) {
+                    *found = true;
+                }
+            }
+        }
+
+        self.markers
+            .iter()
+            .map(|(_, kmers)| {
+                let found = kmers
+                    .iter()
+                    .filter(|kmer| lookup.get(kmer.as_slice()).copied().unwrap_or(false))
+                    .count();
+                found as f32 / kmers.len() as f32
+            })
+            .collect()
+    }
+}
+
+/// The set of canonical (the lesser of forward and reverse complement) k-mers
+/// in a sequence, ignoring case.
+fn canonical_kmers(seq: &[u8], k: usize) -> HashSet<Vec<u8>> {
+    let seq = seq.to_ascii_uppercase();
+    seq.windows(k)
+        .map(|kmer| {
+            let revcomp = reverse_complement(kmer);
+            if revcomp.as_slice() < kmer {
+                revcomp
+            } else {
+                kmer.to_vec()
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,304 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::gfa::gfa::GFAtk;
+use crate::gfa::gfa_string;
+use crate::gfa::graph::SortOrder;
+use crate::load::load_gfa;
+use crate::utils::{get_option_string, reverse_complement, reverse_orientation};
+use anyhow::{bail, Result};
+use gfa::gfa::{Header, Orientation, Path, GFA};
+use gfa::optfields::OptionalFields;
+
+/// How segment names which collide with those of an earlier input are changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rename {
+    /// Prefix the name with the input file stem, e.g. `k31_1`.
+    Prefix,
+    /// Give the segment the next unused integer name.
+    Renumber,
+}
+
+/// Which segments of later inputs are merged into segments of earlier inputs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dedup {
+    /// Keep all segments.
+    None,
+    /// Merge segments with identical sequence.
+    Sequence,
+    /// Merge segments with identical, or reverse complement identical, sequence.
+    ReverseComplement,
+}
+
+/// What happened to a segment of an input in [`merge_gfas`].
+#[derive(Clone, Debug)]
+pub struct MergedSegment {
+    /// Index of the input GFA.
+    pub input: usize,
+    /// The segment name in the input.
+    pub original: Vec<u8>,
+    /// The segment name in the merged GFA.
+    pub name: Vec<u8>,
+    /// Whether the segment is the reverse of `name`, when deduplicated.
+    pub reversed: bool,
+    /// Whether the segment was merged into a segment of an earlier input.
+    pub deduplicated: bool,
+}
+
+/// Merge GFAs into one.
+///
+/// Segments keep their names unless they collide with a segment of an earlier input,
+/// when they are renamed as in [`Rename`]. With [`Dedup`], a segment with the same
+/// sequence (or reverse complement) as one from an earlier input is dropped, and its
+/// links, containments and path steps moved to that segment (reversed, if it was the
+/// reverse complement). Links which are then identical are written once. Colliding
+/// path names are prefixed with the input file stem.
+pub fn merge_gfas(
+    inputs: &[(String, GFAtk)],
+    rename: Rename,
+    dedup: Dedup,
+) -> Result<(GFAtk, Vec<MergedSegment>)> {
+    let all_names: HashSet<&[u8]> = inputs
+        .iter()
+        .flat_map(|(_, gfa)| gfa.0.segments.iter().map(|s| s.name.as_slice()))
+        .collect();
+    let mut next_number = all_names
+        .iter()
+        .filter_map(|n| std::str::from_utf8(n).ok()?.parse::<usize>().ok())
+        .max()
+        .unwrap_or(0)
+        + 1;
+
+    let mut merged: GFA<Vec<u8>, OptionalFields> = GFA::new();
+    merged.header = Header::default();
+    let mut taken: HashSet<Vec<u8>> = HashSet::new();
+    let mut path_names: HashSet<Vec<u8>> = HashSet::new();
+    // sequences of earlier inputs, and the segment they are in
+    let mut sequences: HashMap<Vec<u8>, (Vec<u8>, Orientation)> = HashMap::new();
+    let mut seen_links = HashSet::new();
+    let mut report = Vec::new();
+
+    for (input, (stem, gfa)) in inputs.iter().enumerate() {
+        let mut new_names: HashMap<&[u8], (Vec<u8>, Orientation)> = HashMap::new();
+        let mut input_sequences = Vec::new();
+
+        for segment in &gfa.0.segments {
+            if let Some((existing, orientation)) = match dedup {
+                Dedup::None => None,
+                _ => sequences.get(&segment.sequence).cloned(),
+            } {
+                report.push(MergedSegment {
+                    input,
+                    original: segment.name.clone(),
+                    name: existing.clone(),
+                    reversed: orientation == Orientation::Backward,
+                    deduplicated: true,
+                });
+                new_names.insert(&segment.name, (existing, orientation));
+                continue;
+            }
+
+            let name = if !taken.contains(&segment.name) {
+                segment.name.clone()
+            } else {
+                match rename {
+                    Rename::Prefix => {
+                        let name = format!("{}_{}", stem, String::from_utf8_lossy(&segment.name))
+                            .into_bytes();
+                        if taken.contains(&name) || all_names.contains(name.as_slice()) {
+                            bail!(
+                                "Prefixed segment name {} is already in use. Try renumbering.",
+                                String::from_utf8_lossy(&name)
+                            );
+                        }
+                        name
+                    }
+                    Rename::Renumber => {
+                        while all_names.contains(next_number.to_string().as_bytes())
+                            || taken.contains(next_number.to_string().as_bytes())
+                        {
+                            next_number += 1;
+                        }
+                        next_number += 1;
+                        (next_number - 1).to_string().into_bytes()
+                    }
+                }
+            };
+
+            taken.insert(name.clone());
+            report.push(MergedSegment {
+                input,
+                original: segment.name.clone(),
+                name: name.clone(),
+                reversed: false,
+                deduplicated: false,
+            });
+            new_names.insert(&segment.name, (name.clone(), Orientation::Forward));
+            input_sequences.push((segment.sequence.clone(), name.clone()));
+
+            let mut segment = segment.clone();
+            segment.name = name;
+            merged.segments.push(segment);
+        }
+
+        // only deduplicate across inputs
+        for (sequence, name) in input_sequences {
+            if dedup == Dedup::ReverseComplement {
+                sequences
+                    .entry(reverse_complement(&sequence))
+                    .or_insert((name.clone(), Orientation::Backward));
+            }
+            sequences
+                .entry(sequence)
+                .or_insert((name, Orientation::Forward));
+        }
+
+        let lookup = |name: &[u8], orientation: Orientation| -> Result<(Vec<u8>, Orientation)> {
+            match new_names.get(name) {
+                Some((new, Orientation::Forward)) => Ok((new.clone(), orientation)),
+                Some((new, Orientation::Backward)) => {
+                    Ok((new.clone(), reverse_orientation(orientation)))
+                }
+                None => bail!(
+                    "Segment {} not found in input {}.",
+                    String::from_utf8_lossy(name),
+                    stem
+                ),
+            }
+        };
+
+        for link in &gfa.0.links {
+            let (from, from_orient) = lookup(&link.from_segment, link.from_orient)?;
+            let (to, to_orient) = lookup(&link.to_segment, link.to_orient)?;
+            if !seen_links.insert((from.clone(), from_orient, to.clone(), to_orient)) {
+                continue;
+            }
+            let mut link = link.clone();
+            (link.from_segment, link.from_orient) = (from, from_orient);
+            (link.to_segment, link.to_orient) = (to, to_orient);
+            merged.links.push(link);
+        }
+
+        for containment in &gfa.0.containments {
+            let mut containment = containment.clone();
+            (containment.container_name, containment.container_orient) =
+                lookup(&containment.container_name, containment.container_orient)?;
+            (containment.contained_name, containment.contained_orient) =
+                lookup(&containment.contained_name, containment.contained_orient)?;
+            merged.containments.push(containment);
+        }
+
+        for path in &gfa.0.paths {
+            let mut steps = Vec::new();
+            for (name, orientation) in path.iter() {
+                let (name, orientation) = lookup(name, orientation)?;
+                steps.push(format!("{}{}", String::from_utf8_lossy(&name), orientation));
+            }
+            let mut path_name = path.path_name.clone();
+            if path_names.contains(&path_name) {
+                path_name =
+                    format!("{}_{}", stem, String::from_utf8_lossy(&path_name)).into_bytes();
+            }
+            path_names.insert(path_name.clone());
+            merged.paths.push(Path::new(
+                path_name,
+                steps.join(",").into_bytes(),
+                path.overlaps.clone(),
+                path.optional.clone(),
+            ));
+        }
+    }
+
+    Ok((GFAtk(merged), report))
+}
+
+/// Merge several GFA files into one, renaming colliding segments, and optionally
+/// deduplicating segments with the same sequence.
+///
+/// The merged GFA is printed to STDOUT, with a single version (`VN`) header. The
+/// other tags of the header of each input are kept on an extra H line, tagged with
+/// the input file (`fn:Z:<file>`).
+///
+/// For example:
+/// ```bash
+/// gfatk merge k31.gfa k51.gfa --dedup reverse-complement > merged.gfa
+/// ```
+pub fn merge(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_files: Vec<&PathBuf> = matches
+        .get_many::<PathBuf>("GFA")
+        .expect("required by clap")
+        .collect();
+    let rename = match matches
+        .get_one::<String>("rename")
+        .expect("defaulted by clap")
+        .as_str()
+    {
+        "renumber" => Rename::Renumber,
+        _ => Rename::Prefix,
+    };
+    let dedup = match matches
+        .get_one::<String>("dedup")
+        .expect("defaulted by clap")
+        .as_str()
+    {
+        "sequence" => Dedup::Sequence,
+        "reverse-complement" => Dedup::ReverseComplement,
+        _ => Dedup::None,
+    };
+
+    let mut inputs = Vec::new();
+    for f in &gfa_files {
+        let gfa = match f.extension() {
+            Some(e) if e == "gfa" => GFAtk(load_gfa(f)?),
+            Some(_) => bail!("Input {:?} is not a GFA.", f.as_os_str()),
+            None => bail!("Could not read file {:?}.", f.as_os_str()),
+        };
+        let stem = f
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        inputs.push((stem, gfa));
+    }
+
+    let (merged, report) = merge_gfas(&inputs, rename, dedup)?;
+
+    for segment in &report {
+        if segment.deduplicated {
+            eprintln!(
+                "[+]\tSegment {} of {:?} has the same sequence as {}{}.",
+                String::from_utf8_lossy(&segment.original),
+                gfa_files[segment.input].as_os_str(),
+                String::from_utf8_lossy(&segment.name),
+                if segment.reversed { "-" } else { "+" }
+            );
+        } else if segment.original != segment.name {
+            eprintln!(
+                "[+]\tSegment {} of {:?} renamed to {}.",
+                String::from_utf8_lossy(&segment.original),
+                gfa_files[segment.input].as_os_str(),
+                String::from_utf8_lossy(&segment.name)
+            );
+        }
+    }
+    eprintln!(
+        "[+]\tMerged {} GFA's into {} segments and {} links.",
+        inputs.len(),
+        merged.0.segments.len(),
+        merged.0.links.len()
+    );
+
+    // the header tags of each input, after the header (and version) of the merged GFA
+    let output = gfa_string(&merged.0, SortOrder::from_matches(matches));
+    let (header, rest) = output.split_once('\n').unwrap_or((&output, ""));
+    println!("{}", header);
+    for (f, (_, gfa)) in gfa_files.iter().zip(&inputs) {
+        let optional = match gfa.0.header.optional.is_empty() {
+            true => String::new(),
+            false => format!("\t{}", get_option_string(gfa.0.header.optional.clone())?),
+        };
+        println!("H\tfn:Z:{}{}", f.display(), optional);
+    }
+    println!("{}", rest);
+
+    Ok(())
+}
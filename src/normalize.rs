@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::gfa::gfa::GFAtk;
+use crate::gfa::gfa_string;
+use crate::gfa::graph::SortOrder;
+use crate::load::{load_gfa, load_gfa_stdin};
+use crate::utils;
+use anyhow::{bail, Context, Result};
+
+/// Canonicalise the links of a GFA, writing each adjacency once.
+///
+/// Mirrored links (as written by MBG) are collapsed, duplicate links merged, keeping
+/// the highest edge coverage, and links with a missing mirror reported. With
+/// `--mirrors`, every link is written with its mirror instead. The GFA is printed to
+/// STDOUT, and optionally a TSV of the duplicate, coverage mismatched and unmirrored
+/// links is written to `--report`.
+///
+/// For example:
+/// ```bash
+/// gfatk normalize in.gfa > out.gfa
+/// ```
+pub fn normalize(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let mirrors = matches.get_flag("mirrors");
+    let report_file = matches.get_one::<PathBuf>("report");
+
+    let gfa: GFAtk = match gfa_file {
+        Some(f) => {
+            let ext = f.extension();
+            match ext {
+                Some(e) => {
+                    if e == "gfa" {
+                        GFAtk(load_gfa(f)?)
+                    } else {
+                        bail!("Input is not a GFA.")
+                    }
+                }
+                None => bail!("Could not read file."),
+            }
+        }
+        None => match utils::is_stdin() {
+            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            false => bail!("No input from STDIN. Run `gfatk normalize -h` for help."),
+        },
+    };
+
+    let (normalized, report) = gfa.normalize_links(mirrors)?;
+
+    eprintln!(
+        "[+]\t{} links in, {} links out: {} mirror pair(s), {} duplicate(s), {} coverage mismatch(es), {} missing mirror(s).",
+        report.links_in,
+        report.links_out,
+        report.mirror_pairs,
+        report.duplicates.len(),
+        report.coverage_mismatches.len(),
+        report.missing_mirrors.len()
+    );
+
+    if let Some(report_file) = report_file {
+        let mut tsv = String::from("link\tissue\tcoverages\n");
+        for link in &report.duplicates {
+            tsv += &format!("{}\tduplicate\t\n", link);
+        }
+        for (link, coverages) in &report.coverage_mismatches {
+            let coverages = coverages
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+            tsv += &format!("{}\tcoverage_mismatch\t{}\n", link, coverages);
+        }
+        for link in &report.missing_mirrors {
+            tsv += &format!("{}\tmissing_mirror\t\n", link);
+        }
+        fs::write(report_file, tsv)
+            .with_context(|| format!("Could not write {:?}", report_file.as_os_str()))?;
+    }
+
+    println!(
+        "{}",
+        gfa_string(&normalized.0, SortOrder::from_matches(matches))
+    );
+
+    Ok(())
+}
@@ -0,0 +1,306 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::gfa::gfa::GFAtk;
+use crate::gfa::gfa_string;
+use crate::gfa::graph::SortOrder;
+use crate::load::{load_gfa, load_gfa_stdin};
+use crate::utils::{self, reverse_cigar, reverse_complement, reverse_orientation};
+use anyhow::{bail, Context, Result};
+use gfa::gfa::{Link, Orientation, Path};
+
+/// The segments flipped in a weakly connected component by [`orient_gfa`].
+#[derive(Clone, Debug)]
+pub struct ComponentOrientation {
+    /// Index of the weakly connected component, as in `gfatk stats`.
+    pub component: usize,
+    /// The number of links, counting a link and its mirror once.
+    pub links: usize,
+    /// The number of those links which could be written `+` to `+` before.
+    pub forward_before: usize,
+    /// And after.
+    pub forward_after: usize,
+    /// The segments flipped.
+    pub flipped: Vec<Vec<u8>>,
+}
+
+/// Whether a link is `+` to `+` (or its mirror is) once its segments are flipped.
+fn forward(
+    from_orient: Orientation,
+    from_flip: bool,
+    to_orient: Orientation,
+    to_flip: bool,
+) -> bool {
+    (from_orient.is_reverse() ^ from_flip) == (to_orient.is_reverse() ^ to_flip)
+}
+
+/// Flip segments so that as many links as possible are `+` to `+`.
+///
+/// Flipping a segment reverse complements its sequence and switches its orientation
+/// in links and paths. In each component, segments are first flipped to agree with
+/// the longest segment, breadth first, and then one at a time while that makes more
+/// links `+` to `+`. Flipping every segment of a component changes nothing, so the
+/// fewer are flipped. Links left `-` to `-` are written as their mirror (with the
+/// overlap reversed), unless that is already in the GFA. Containment (C) lines on flipped segments are removed.
+pub fn orient_gfa(gfa: &GFAtk) -> Result<(GFAtk, Vec<ComponentOrientation>)> {
+    let (graph_indices, gfa_graph) = gfa.into_digraph()?;
+    let components = gfa_graph.weakly_connected_components(graph_indices)?;
+
+    let lengths: HashMap<&[u8], usize> = gfa
+        .0
+        .segments
+        .iter()
+        .map(|s| (s.name.as_slice(), s.sequence.len()))
+        .collect();
+
+    // links as (neighbour, whether the two must be flipped alike), hairpins aside
+    let unique_links = gfa.unique_links();
+    let mut adjacency: HashMap<&[u8], Vec<(&[u8], bool)>> = HashMap::new();
+    for link in &unique_links {
+        if link.from_segment == link.to_segment {
+            continue;
+        }
+        let alike = link.from_orient == link.to_orient;
+        adjacency
+            .entry(&link.from_segment)
+            .or_default()
+            .push((&link.to_segment, alike));
+        adjacency
+            .entry(&link.to_segment)
+            .or_default()
+            .push((&link.from_segment, alike));
+    }
+
+    let mut flip: HashMap<&[u8], bool> = HashMap::new();
+    let mut report = Vec::new();
+
+    for (index, component) in components.iter().enumerate() {
+        let Some(anchor) = component.iter().max_by_key(|s| lengths[s.as_slice()]) else {
+            continue;
+        };
+
+        // agree with the anchor, breadth first
+        let mut queue = std::collections::VecDeque::from([(anchor.as_slice(), false)]);
+        while let Some((segment, flipped)) = queue.pop_front() {
+            if flip.contains_key(segment) {
+                continue;
+            }
+            flip.insert(segment, flipped);
+            for (neighbour, alike) in adjacency.get(segment).into_iter().flatten() {
+                if !flip.contains_key(neighbour) {
+                    queue.push_back((neighbour, flipped ^ !alike));
+                }
+            }
+        }
+
+        // then flip any segment which makes more links agree, until none do
+        let agreeing = |segment: &[u8], flipped: bool, flip: &HashMap<&[u8], bool>| {
+            adjacency
+                .get(segment)
+                .into_iter()
+                .flatten()
+                .filter(|(n, alike)| (flipped == flip[n]) == *alike)
+                .count()
+        };
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for segment in component {
+                let flipped = flip[segment.as_slice()];
+                if agreeing(segment, !flipped, &flip) > agreeing(segment, flipped, &flip) {
+                    flip.insert(segment, !flipped);
+                    improved = true;
+                }
+            }
+        }
+
+        let flipped_count = component.iter().filter(|s| flip[s.as_slice()]).count();
+        if flipped_count * 2 > component.len() {
+            for segment in component {
+                let flipped = flip[segment.as_slice()];
+                flip.insert(segment, !flipped);
+            }
+        }
+
+        let segments: HashSet<&[u8]> = component.iter().map(|s| s.as_slice()).collect();
+        let links: Vec<_> = unique_links
+            .iter()
+            .filter(|l| segments.contains(l.from_segment.as_slice()))
+            .collect();
+        report.push(ComponentOrientation {
+            component: index,
+            links: links.len(),
+            forward_before: links
+                .iter()
+                .filter(|l| forward(l.from_orient, false, l.to_orient, false))
+                .count(),
+            forward_after: links
+                .iter()
+                .filter(|l| {
+                    forward(
+                        l.from_orient,
+                        flip[l.from_segment.as_slice()],
+                        l.to_orient,
+                        flip[l.to_segment.as_slice()],
+                    )
+                })
+                .count(),
+            flipped: component
+                .iter()
+                .filter(|s| flip[s.as_slice()])
+                .cloned()
+                .collect(),
+        });
+    }
+
+    let is_flipped = |name: &[u8]| flip.get(name).copied().unwrap_or(false);
+    let orient = |name: &[u8], orientation: Orientation| match is_flipped(name) {
+        true => reverse_orientation(orientation),
+        false => orientation,
+    };
+
+    let mut oriented = gfa.0.clone();
+    for segment in oriented.segments.iter_mut() {
+        if is_flipped(&segment.name) {
+            segment.sequence = reverse_complement(&segment.sequence);
+        }
+    }
+
+    let links: Vec<Link<Vec<u8>, _>> = gfa
+        .0
+        .links
+        .iter()
+        .map(|l| Link {
+            from_orient: orient(&l.from_segment, l.from_orient),
+            to_orient: orient(&l.to_segment, l.to_orient),
+            ..l.clone()
+        })
+        .collect();
+    let written: HashSet<_> = links
+        .iter()
+        .map(|l| (&l.from_segment, l.from_orient, &l.to_segment, l.to_orient))
+        .collect();
+    oriented.links = links
+        .iter()
+        .map(|l| {
+            let minus =
+                l.from_orient == Orientation::Backward && l.to_orient == Orientation::Backward;
+            let mirror = (
+                &l.to_segment,
+                Orientation::Forward,
+                &l.from_segment,
+                Orientation::Forward,
+            );
+            match minus && !written.contains(&mirror) {
+                true => Ok(Link {
+                    from_segment: l.to_segment.clone(),
+                    from_orient: Orientation::Forward,
+                    to_segment: l.from_segment.clone(),
+                    to_orient: Orientation::Forward,
+                    overlap: reverse_cigar(&l.overlap)?,
+                    ..l.clone()
+                }),
+                false => Ok(l.clone()),
+            }
+        })
+        .collect::<Result<_>>()?;
+
+    oriented
+        .containments
+        .retain(|c| !is_flipped(&c.container_name) && !is_flipped(&c.contained_name));
+
+    oriented.paths = gfa
+        .0
+        .paths
+        .iter()
+        .map(|p| {
+            let steps: Vec<String> = p
+                .iter()
+                .map(|(name, orientation)| {
+                    format!("{}{}", name, orient(name.as_ref(), orientation))
+                })
+                .collect();
+            Path::new(
+                p.path_name.clone(),
+                steps.join(",").into_bytes(),
+                p.overlaps.clone(),
+                p.optional.clone(),
+            )
+        })
+        .collect();
+
+    Ok((GFAtk(oriented), report))
+}
+
+/// Flip segments of a GFA so that as many links as possible are `+` to `+`.
+///
+/// The oriented GFA is printed to STDOUT, and optionally a TSV of the segments flipped
+/// in each component is written to `--report`.
+///
+/// For example:
+/// ```bash
+/// gfatk orient in.gfa | gfatk dot | dot -Tsvg > out.svg
+/// ```
+pub fn orient(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let report_file = matches.get_one::<PathBuf>("report");
+
+    let gfa: GFAtk = match gfa_file {
+        Some(f) => {
+            let ext = f.extension();
+            match ext {
+                Some(e) => {
+                    if e == "gfa" {
+                        GFAtk(load_gfa(f)?)
+                    } else {
+                        bail!("Input is not a GFA.")
+                    }
+                }
+                None => bail!("Could not read file."),
+            }
+        }
+        None => match utils::is_stdin() {
+            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            false => bail!("No input from STDIN. Run `gfatk orient -h` for help."),
+        },
+    };
+
+    let gfa = gfa.sorted(matches);
+
+    let (oriented, components) = orient_gfa(&gfa)?;
+
+    let flipped: usize = components.iter().map(|c| c.flipped.len()).sum();
+    eprintln!(
+        "[+]\tFlipped {} segment(s): {} of {} links are + to + (from {}).",
+        flipped,
+        components.iter().map(|c| c.forward_after).sum::<usize>(),
+        components.iter().map(|c| c.links).sum::<usize>(),
+        components.iter().map(|c| c.forward_before).sum::<usize>()
+    );
+    if flipped > 0 && !gfa.0.containments.is_empty() {
+        eprintln!("[-]\tContainment (C) lines on flipped segments are removed.");
+    }
+
+    if let Some(report_file) = report_file {
+        let mut report = String::from("segment\tcomponent\n");
+        for component in &components {
+            for segment in &component.flipped {
+                report += &format!(
+                    "{}\t{}\n",
+                    String::from_utf8_lossy(segment),
+                    component.component
+                );
+            }
+        }
+        fs::write(report_file, report)
+            .with_context(|| format!("Could not write {:?}", report_file.as_os_str()))?;
+    }
+
+    println!(
+        "{}",
+        gfa_string(&oriented.0, SortOrder::from_matches(matches))
+    );
+
+    Ok(())
+}
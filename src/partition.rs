@@ -0,0 +1,384 @@
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::gfa::gfa::GFAtk;
+use crate::gfa::gfa_string;
+use crate::gfa::graph::{segments_subgraph, SortOrder};
+use crate::load::{load_gfa, load_gfa_stdin};
+use crate::markers::Markers;
+use crate::preset::{get_preset, Preset};
+use crate::stats::{self, GenomeType, Stats};
+use crate::utils;
+use anyhow::{bail, Context, Result};
+
+/// The class each weakly connected component of a GFA is assigned to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Partition {
+    /// The mitochondrial genome.
+    Mitochondria,
+    /// The plastid genome.
+    Plastid,
+    /// Nuclear sequence, at around the background coverage of the assembly.
+    Nuclear,
+    /// High coverage, but outside the organelle bounds, or claimed by both
+    /// organelles with no way to choose between them.
+    Unassigned,
+}
+
+impl Partition {
+    /// All of the partitions, in output order.
+    pub const ALL: [Partition; 4] = [
+        Partition::Mitochondria,
+        Partition::Plastid,
+        Partition::Nuclear,
+        Partition::Unassigned,
+    ];
+}
+
+impl fmt::Display for Partition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Partition::Mitochondria => write!(f, "mito"),
+            Partition::Plastid => write!(f, "plastid"),
+            Partition::Nuclear => write!(f, "nuclear"),
+            Partition::Unassigned => write!(f, "unassigned"),
+        }
+    }
+}
+
+/// Which organelle wins a component claimed by both, with equal scores.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Prefer {
+    /// Give the component to the mitochondria.
+    Mitochondria,
+    /// Give the component to the plastid.
+    Plastid,
+    /// Leave the component unassigned.
+    None,
+}
+
+/// The score of a component as a single organelle, and whether
+/// the organelle claims it.
+#[derive(Clone, Copy, Debug)]
+pub struct Claim {
+    /// The score, as in [`Stats::score_organelle`].
+    pub score: f32,
+    /// Whether the score is high enough to claim the component.
+    pub claimed: bool,
+}
+
+/// The partition a component is assigned to, and why.
+#[derive(Clone, Debug)]
+pub struct Assignment {
+    /// Index of the component, as in [`stats::Stat`].
+    pub index: usize,
+    /// The assigned partition.
+    pub partition: Partition,
+    /// The mitochondrial claim on this component.
+    pub mito: Claim,
+    /// The plastid claim on this component.
+    pub plastid: Claim,
+    /// A human readable reason for the assignment.
+    pub reason: String,
+}
+
+/// Score every component in [`Stats`] as one organelle.
+///
+/// Without markers, a component is claimed if both its GC% and size are within
+/// the preset bounds (scoring 0.25 each). With markers, the score of
+/// [`Stats::score_organelle`] must reach `min_score`.
+pub fn organelle_claims(
+    store_stats: &Stats,
+    gfa: &GFAtk,
+    preset: &Preset,
+    markers: Option<&Markers>,
+    min_containment: f32,
+    min_score: f32,
+) -> Result<Vec<Claim>> {
+    match markers {
+        Some(m) => Ok(store_stats
+            .score_organelle(gfa, preset, m, min_containment)?
+            .iter()
+            .map(|s| Claim {
+                score: s.score,
+                claimed: s.score >= min_score,
+            })
+            .collect()),
+        None => Ok(store_stats
+            .0
+            .iter()
+            .map(|stat| {
+                let (gc_in_range, size_in_range) = Stats::in_organelle_bounds(stat, preset);
+                Claim {
+                    score: 0.25 * gc_in_range as u8 as f32 + 0.25 * size_in_range as u8 as f32,
+                    claimed: gc_in_range && size_in_range,
+                }
+            })
+            .collect()),
+    }
+}
+
+/// Assign each component to a [`Partition`].
+///
+/// Components claimed by one organelle go to it. Components claimed by both go
+/// to the higher scoring organelle, and on a tie to the `prefer`red organelle,
+/// or are left unassigned. Of the rest, those with coverage at least
+/// `coverage_ratio` times the nuclear coverage (the length weighted median
+/// coverage of the unclaimed components) are unassigned, as they look like
+/// organelle (or other high copy) sequence. Everything else is nuclear, as are
+/// all unclaimed components if their nuclear coverage is zero.
+pub fn assign_partitions(
+    store_stats: &Stats,
+    mito: &[Claim],
+    plastid: &[Claim],
+    prefer: Prefer,
+    coverage_ratio: f32,
+) -> Vec<Assignment> {
+    let nuclear_coverage = utils::weighted_median(
+        &store_stats
+            .0
+            .iter()
+            .filter(|stat| !mito[stat.index].claimed && !plastid[stat.index].claimed)
+            .map(|stat| (stat.cov, stat.corrected_sequence_length))
+            .collect::<Vec<_>>(),
+    )
+    .filter(|c| *c > 0.0);
+
+    let mut assignments = Vec::new();
+
+    for stat in &store_stats.0 {
+        let (m, p) = (mito[stat.index], plastid[stat.index]);
+
+        let (partition, reason) = match (m.claimed, p.claimed) {
+            (true, false) => (Partition::Mitochondria, "claimed by mito only".to_string()),
+            (false, true) => (Partition::Plastid, "claimed by plastid only".to_string()),
+            (true, true) => {
+                let claims = format!(
+                    "claimed by mito (score {}) and plastid (score {})",
+                    m.score, p.score
+                );
+                if m.score > p.score {
+                    (
+                        Partition::Mitochondria,
+                        format!("{claims}; mito scores higher"),
+                    )
+                } else if p.score > m.score {
+                    (
+                        Partition::Plastid,
+                        format!("{claims}; plastid scores higher"),
+                    )
+                } else {
+                    match prefer {
+                        Prefer::Mitochondria => (
+                            Partition::Mitochondria,
+                            format!("{claims}; tie, mito preferred"),
+                        ),
+                        Prefer::Plastid => (
+                            Partition::Plastid,
+                            format!("{claims}; tie, plastid preferred"),
+                        ),
+                        Prefer::None => {
+                            (Partition::Unassigned, format!("{claims}; tie, unresolved"))
+                        }
+                    }
+                }
+            }
+            (false, false) => match nuclear_coverage {
+                None => (
+                    Partition::Nuclear,
+                    "not claimed; no nuclear coverage to compare to".to_string(),
+                ),
+                Some(nuclear_coverage) => {
+                    let ratio = stat.cov / nuclear_coverage;
+                    if ratio >= coverage_ratio {
+                        (
+                            Partition::Unassigned,
+                            format!(
+                                "not claimed; coverage {ratio:.2}x nuclear ({nuclear_coverage}) is organelle-like"
+                            ),
+                        )
+                    } else {
+                        (
+                            Partition::Nuclear,
+                            format!(
+                                "not claimed; coverage {ratio:.2}x nuclear ({nuclear_coverage})"
+                            ),
+                        )
+                    }
+                }
+            },
+        };
+
+        assignments.push(Assignment {
+            index: stat.index,
+            partition,
+            mito: m,
+            plastid: p,
+            reason,
+        });
+    }
+
+    assignments
+}
+
+/// Partition every weakly connected component of a GFA into mitochondria, plastid,
+/// nuclear and unassigned, in a single pass.
+///
+/// Writes one GFA per partition (`<prefix>.mito.gfa`, `<prefix>.plastid.gfa`,
+/// `<prefix>.nuclear.gfa` and `<prefix>.unassigned.gfa`), and prints a TSV of
+/// each component's stats and the reason for its assignment.
+///
+/// For example:
+/// ```bash
+/// gfatk partition in.gfa -o out > partition.tsv
+/// ```
+pub fn partition(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let output_prefix = matches
+        .get_one::<String>("output-prefix")
+        .expect("defaulted by clap");
+    let preset_file = matches.get_one::<PathBuf>("preset-file");
+    let mito_preset = get_preset(
+        matches
+            .get_one::<String>("mito-preset")
+            .expect("defaulted by clap"),
+        GenomeType::Mitochondria,
+        preset_file,
+    )?;
+    let plastid_preset = get_preset(
+        matches
+            .get_one::<String>("plastid-preset")
+            .expect("defaulted by clap"),
+        GenomeType::Chloroplast,
+        preset_file,
+    )?;
+    let kmer_size = *matches
+        .get_one::<usize>("kmer-size")
+        .expect("defaulted by clap");
+    let min_containment = *matches
+        .get_one::<f32>("min-containment")
+        .expect("defaulted by clap");
+    let min_score = *matches
+        .get_one::<f32>("min-score")
+        .expect("defaulted by clap");
+    let mito_markers = match matches.get_one::<PathBuf>("mito-markers") {
+        Some(m) => Some(Markers::from_fasta(m, kmer_size)?),
+        None => None,
+    };
+    let plastid_markers = match matches.get_one::<PathBuf>("plastid-markers") {
+        Some(m) => Some(Markers::from_fasta(m, kmer_size)?),
+        None => None,
+    };
+    let prefer = match matches
+        .get_one::<String>("prefer")
+        .expect("defaulted by clap")
+        .as_str()
+    {
+        "mito" => Prefer::Mitochondria,
+        "plastid" => Prefer::Plastid,
+        _ => Prefer::None,
+    };
+    let coverage_ratio = *matches
+        .get_one::<f32>("organelle-coverage")
+        .expect("defaulted by clap");
+
+    let gfa: GFAtk = match gfa_file {
+        Some(f) => {
+            let ext = f.extension();
+            match ext {
+                Some(e) => {
+                    if e == "gfa" {
+                        GFAtk(load_gfa(f)?)
+                    } else {
+                        bail!("Input is not a GFA.")
+                    }
+                }
+                None => bail!("Could not read file."),
+            }
+        }
+        None => match utils::is_stdin() {
+            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            false => bail!("No input from STDIN. Run `gfatk partition -h` for help."),
+        },
+    };
+
+    let gfa = gfa.sorted(matches);
+
+    // the stats are computed once, and shared between the organelles
+    let store_stats = stats::subgraph_stats(&gfa, GenomeType::Mitochondria, true)?;
+    if store_stats.0.is_empty() {
+        bail!("There were no segments to be partitioned. Check input GFA file.");
+    }
+
+    let mito = organelle_claims(
+        &store_stats,
+        &gfa,
+        &mito_preset,
+        mito_markers.as_ref(),
+        min_containment,
+        min_score,
+    )?;
+    let plastid = organelle_claims(
+        &store_stats,
+        &gfa,
+        &plastid_preset,
+        plastid_markers.as_ref(),
+        min_containment,
+        min_score,
+    )?;
+
+    let assignments = assign_partitions(&store_stats, &mito, &plastid, prefer, coverage_ratio);
+
+    println!("component\tpartition\tnode_count\tedge_count\ttotal_sequence_length\tcorrected_sequence_length\tgc\tcoverage\tcircularity\tmito_score\tplastid_score\treason\tsegments");
+
+    for assignment in &assignments {
+        let stat = &store_stats.0[assignment.index];
+        let segment_string = stat
+            .segments
+            .iter()
+            .map(|s| String::from_utf8_lossy(s).to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            assignment.index,
+            assignment.partition,
+            stat.node_count,
+            stat.edge_count,
+            stat.total_sequence_length,
+            stat.corrected_sequence_length,
+            stat.gc,
+            stat.cov,
+            stat.circularity,
+            assignment.mito.score,
+            assignment.plastid.score,
+            assignment.reason,
+            segment_string
+        );
+    }
+
+    for partition in Partition::ALL {
+        let segments: Vec<Vec<u8>> = assignments
+            .iter()
+            .filter(|a| a.partition == partition)
+            .flat_map(|a| store_stats.0[a.index].segments.clone())
+            .collect();
+        let components = assignments
+            .iter()
+            .filter(|a| a.partition == partition)
+            .count();
+
+        let file_name = format!("{}.{}.gfa", output_prefix, partition);
+        let subgraph = segments_subgraph(&gfa.0, segments);
+        fs::write(
+            &file_name,
+            gfa_string(&subgraph, SortOrder::from_matches(matches)),
+        )
+        .with_context(|| format!("Could not write {}", file_name))?;
+
+        eprintln!("[+]\t{} component(s) written to {}", components, file_name);
+    }
+
+    Ok(())
+}
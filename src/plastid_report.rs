@@ -0,0 +1,254 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use crate::gfa::gfa::GFAtk;
+use crate::gfa::graph::segments_subgraph;
+use crate::load::{load_gfa, load_gfa_stdin};
+use crate::preset::organelle_preset;
+use crate::stats::{self, GenomeType};
+use crate::utils::{self, parse_cigar, reverse_orientation};
+use anyhow::{bail, Context, Result};
+
+/// Segments below this coverage, relative to the single copy coverage, are single copy.
+const SINGLE_COPY_MAX_RATIO: f32 = 1.5;
+/// Segments below this relative coverage (and not single copy) are inverted repeat.
+/// Anything above is some other repeat, and ignored.
+const INVERTED_REPEAT_MAX_RATIO: f32 = 2.5;
+
+/// A region of the plastid quadripartite structure.
+#[derive(Clone, Debug)]
+pub struct PlastidRegion {
+    /// The segments making up this region.
+    pub segments: Vec<Vec<u8>>,
+    /// The length of the region, with overlaps between its segments removed.
+    pub length: usize,
+    /// The length weighted coverage of the region.
+    pub coverage: f32,
+}
+
+/// The inferred quadripartite structure of a plastid subgraph.
+///
+/// A large single copy region (LSC) and a small single copy region (SSC)
+/// are separated by two copies of an inverted repeat (IR). In an assembly
+/// graph the inverted repeat is usually collapsed, and so sits at roughly
+/// twice the coverage of the single copy regions.
+#[derive(Clone, Debug)]
+pub struct Quadripartite {
+    /// The large single copy region.
+    pub lsc: PlastidRegion,
+    /// The small single copy region.
+    pub ssc: PlastidRegion,
+    /// The (collapsed) inverted repeat.
+    pub ir: PlastidRegion,
+    /// The estimated coverage of a single copy region.
+    pub single_copy_coverage: f32,
+}
+
+impl Quadripartite {
+    /// The inferred length of the whole plastid, counting the inverted repeat twice.
+    pub fn total_length(&self) -> usize {
+        self.lsc.length + self.ssc.length + 2 * self.ir.length
+    }
+}
+
+/// Infer the quadripartite structure of a plastid (sub)graph from the coverage
+/// and topology of its segments.
+///
+/// The single copy coverage is estimated as the length weighted median of the segment
+/// coverages. Segments at roughly twice this coverage (between `SINGLE_COPY_MAX_RATIO`
+/// and `INVERTED_REPEAT_MAX_RATIO` times) are assigned to the inverted repeat.
+/// The remaining single copy segments must then fall into exactly two weakly connected
+/// components, each of which is joined to the inverted repeat. The longer is the LSC, the
+/// shorter is the SSC.
+///
+/// Returns `None` if no such structure is found.
+pub fn infer_quadripartite(gfa: &GFAtk) -> Result<Option<Quadripartite>> {
+    let mut len_cov = Vec::new();
+    for segment in &gfa.0.segments {
+        let (len, cov) = gfa.node_seq_len_and_cov(segment.name.clone())?;
+        len_cov.push((segment.name.clone(), len, cov));
+    }
+
+    let single_copy_coverage = match utils::weighted_median(
+        &len_cov
+            .iter()
+            .map(|(_, len, cov)| (*cov, *len))
+            .collect::<Vec<_>>(),
+    ) {
+        Some(c) => c,
+        None => return Ok(None),
+    };
+
+    // split the segments on their relative coverage
+    let mut single_copy = Vec::new();
+    let mut inverted_repeat = Vec::new();
+    for (name, _, cov) in &len_cov {
+        let ratio = cov / single_copy_coverage;
+        if ratio < SINGLE_COPY_MAX_RATIO {
+            single_copy.push(name.clone());
+        } else if ratio < INVERTED_REPEAT_MAX_RATIO {
+            inverted_repeat.push(name.clone());
+        }
+    }
+
+    if inverted_repeat.is_empty() {
+        return Ok(None);
+    }
+
+    // the single copy regions are the subgraphs left over once the IR is removed
+    let single_copy_gfa = GFAtk(segments_subgraph(&gfa.0, single_copy));
+    let (graph_indices, single_copy_graph) = single_copy_gfa.into_digraph()?;
+    let single_copy_regions = single_copy_graph.weakly_connected_components(graph_indices)?;
+
+    if single_copy_regions.len() != 2 {
+        return Ok(None);
+    }
+
+    // and both of these must be joined to the IR
+    for region in &single_copy_regions {
+        let joined = gfa.0.links.iter().any(|l| {
+            (region.contains(&l.from_segment) && inverted_repeat.contains(&l.to_segment))
+                || (region.contains(&l.to_segment) && inverted_repeat.contains(&l.from_segment))
+        });
+        if !joined {
+            return Ok(None);
+        }
+    }
+
+    let mut regions = single_copy_regions
+        .into_iter()
+        .map(|r| make_region(gfa, r, &len_cov))
+        .collect::<Result<Vec<_>>>()?;
+    regions.sort_by_key(|r| std::cmp::Reverse(r.length));
+    let ssc = regions.pop().context("Two single copy regions expected.")?;
+    let lsc = regions.pop().context("Two single copy regions expected.")?;
+    let ir = make_region(gfa, inverted_repeat, &len_cov)?;
+
+    Ok(Some(Quadripartite {
+        lsc,
+        ssc,
+        ir,
+        single_copy_coverage,
+    }))
+}
+
+/// Compute the length and coverage of a set of segments.
+///
+/// Each link within the region (counting a link and its mirror once) has its
+/// overlap removed from the length.
+fn make_region(
+    gfa: &GFAtk,
+    segments: Vec<Vec<u8>>,
+    len_cov: &[(Vec<u8>, usize, f32)],
+) -> Result<PlastidRegion> {
+    let mut length = 0;
+    let mut weighted_cov = 0.0;
+    for (name, len, cov) in len_cov {
+        if segments.contains(name) {
+            length += len;
+            weighted_cov += cov * *len as f32;
+        }
+    }
+    let coverage = weighted_cov / length as f32;
+
+    let mut seen_links = HashSet::new();
+    for link in &gfa.0.links {
+        if !(segments.contains(&link.from_segment) && segments.contains(&link.to_segment)) {
+            continue;
+        }
+        let forward = (
+            link.from_segment.clone(),
+            link.from_orient,
+            link.to_segment.clone(),
+            link.to_orient,
+        );
+        let mirror = (
+            link.to_segment.clone(),
+            reverse_orientation(link.to_orient),
+            link.from_segment.clone(),
+            reverse_orientation(link.from_orient),
+        );
+        if seen_links.contains(&forward) || seen_links.contains(&mirror) {
+            continue;
+        }
+        length = length.saturating_sub(parse_cigar(&link.overlap)?);
+        seen_links.insert(forward);
+    }
+
+    Ok(PlastidRegion {
+        segments,
+        length,
+        coverage,
+    })
+}
+
+/// Report the quadripartite structure (LSC/SSC/IR) of the putative plastid subgraph(s) in a GFA.
+///
+/// Subgraphs are chosen as in `gfatk extract-chloro`, and each is reported separately.
+///
+/// For example:
+/// ```bash
+/// gfatk plastid-report in.gfa > report.tsv
+/// ```
+pub fn plastid_report(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let preset = organelle_preset(matches, GenomeType::Chloroplast)?;
+
+    let gfa: GFAtk = match gfa_file {
+        Some(f) => {
+            let ext = f.extension();
+            match ext {
+                Some(e) => {
+                    if e == "gfa" {
+                        GFAtk(load_gfa(f)?)
+                    } else {
+                        bail!("Input is not a GFA.")
+                    }
+                }
+                None => bail!("Could not read file."),
+            }
+        }
+        None => match utils::is_stdin() {
+            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            false => bail!("No input from STDIN. Run `gfatk plastid-report -h` for help."),
+        },
+    };
+
+    let store_stats = stats::subgraph_stats(&gfa, GenomeType::Chloroplast, false)?;
+    let plastids = store_stats.filter_organelle(&preset)?;
+
+    println!("subgraph_index\tregion\tlength\tcoverage\tsegments");
+
+    for stat in plastids {
+        let subgraph_gfa = GFAtk(segments_subgraph(&gfa.0, stat.segments.clone()));
+
+        match infer_quadripartite(&subgraph_gfa)? {
+            Some(q) => {
+                eprintln!(
+                    "[+]\tSubgraph {}: quadripartite structure found (single copy coverage: {}, inferred length: {}).",
+                    stat.index,
+                    q.single_copy_coverage,
+                    q.total_length()
+                );
+                for (name, region) in [("LSC", &q.lsc), ("SSC", &q.ssc), ("IR", &q.ir)] {
+                    let segment_string = region
+                        .segments
+                        .iter()
+                        .map(|s| String::from_utf8_lossy(s).to_string())
+                        .collect::<Vec<String>>()
+                        .join(",");
+                    println!(
+                        "{}\t{}\t{}\t{}\t{}",
+                        stat.index, name, region.length, region.coverage, segment_string
+                    );
+                }
+            }
+            None => eprintln!(
+                "[-]\tSubgraph {}: no quadripartite structure found.",
+                stat.index
+            ),
+        }
+    }
+
+    Ok(())
+}
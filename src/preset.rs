@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::stats::GenomeType;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+/// The size and GC% bounds used to pick out an organelle subgraph from a GFA.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Preset {
+    /// Minimum size (bp) of the organelle.
+    pub size_lower: usize,
+    /// Maximum size (bp) of the organelle.
+    pub size_upper: usize,
+    /// Minimum GC% of the organelle.
+    pub gc_lower: f32,
+    /// Maximum GC% of the organelle.
+    pub gc_upper: f32,
+    /// Added to the maximum size, as a tolerance for collapsed
+    /// repeats or uneven assemblies.
+    pub size_slack: usize,
+}
+
+/// The built in presets, their names, and the organelle they apply to.
+///
+/// The plant presets are the original defaults of `gfatk extract-mito` and
+/// `gfatk extract-chloro`. The others are rough ranges, and will need
+/// tuning for particular clades.
+pub const PRESETS: &[(&str, GenomeType, Preset)] = &[
+    (
+        "plant-mito",
+        GenomeType::Mitochondria,
+        Preset {
+            size_lower: 200000,
+            size_upper: 1000000,
+            gc_lower: 0.42,
+            gc_upper: 0.50,
+            size_slack: 20000,
+        },
+    ),
+    (
+        "vertebrate-mito",
+        GenomeType::Mitochondria,
+        Preset {
+            size_lower: 14000,
+            size_upper: 20000,
+            gc_lower: 0.35,
+            gc_upper: 0.50,
+            size_slack: 2000,
+        },
+    ),
+    (
+        "invertebrate-mito",
+        GenomeType::Mitochondria,
+        Preset {
+            size_lower: 13000,
+            size_upper: 25000,
+            gc_lower: 0.15,
+            gc_upper: 0.40,
+            size_slack: 2000,
+        },
+    ),
+    (
+        "fungi-mito",
+        GenomeType::Mitochondria,
+        Preset {
+            size_lower: 20000,
+            size_upper: 250000,
+            gc_lower: 0.15,
+            gc_upper: 0.40,
+            size_slack: 10000,
+        },
+    ),
+    (
+        "algae-mito",
+        GenomeType::Mitochondria,
+        Preset {
+            size_lower: 15000,
+            size_upper: 100000,
+            gc_lower: 0.25,
+            gc_upper: 0.50,
+            size_slack: 5000,
+        },
+    ),
+    (
+        "plant-plastid",
+        GenomeType::Chloroplast,
+        Preset {
+            size_lower: 126000,
+            size_upper: 180000,
+            gc_lower: 0.35,
+            gc_upper: 0.39,
+            size_slack: 20000,
+        },
+    ),
+    (
+        "algae-plastid",
+        GenomeType::Chloroplast,
+        Preset {
+            size_lower: 50000,
+            size_upper: 300000,
+            gc_lower: 0.25,
+            gc_upper: 0.45,
+            size_slack: 20000,
+        },
+    ),
+];
+
+/// An entry in a user supplied TOML preset file.
+///
+/// All fields are optional when overriding a built in preset.
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+struct PresetEntry {
+    size_lower: Option<usize>,
+    size_upper: Option<usize>,
+    gc_lower: Option<f32>,
+    gc_upper: Option<f32>,
+    size_slack: Option<usize>,
+}
+
+impl PresetEntry {
+    /// Fill in the missing fields of this entry from a base preset.
+    fn over(self, base: Preset) -> Preset {
+        Preset {
+            size_lower: self.size_lower.unwrap_or(base.size_lower),
+            size_upper: self.size_upper.unwrap_or(base.size_upper),
+            gc_lower: self.gc_lower.unwrap_or(base.gc_lower),
+            gc_upper: self.gc_upper.unwrap_or(base.gc_upper),
+            size_slack: self.size_slack.unwrap_or(base.size_slack),
+        }
+    }
+
+    /// Convert an entry with no base preset, requiring all fields.
+    fn complete(self, name: &str) -> Result<Preset> {
+        let missing = |field: &str| format!("Preset {} in preset file is missing {}.", name, field);
+        Ok(Preset {
+            size_lower: self.size_lower.with_context(|| missing("size_lower"))?,
+            size_upper: self.size_upper.with_context(|| missing("size_upper"))?,
+            gc_lower: self.gc_lower.with_context(|| missing("gc_lower"))?,
+            gc_upper: self.gc_upper.with_context(|| missing("gc_upper"))?,
+            size_slack: self.size_slack.with_context(|| missing("size_slack"))?,
+        })
+    }
+}
+
+/// The name of the default preset for an organelle.
+fn default_preset_name(genome_type: GenomeType) -> Result<&'static str> {
+    match genome_type {
+        GenomeType::Mitochondria => Ok("plant-mito"),
+        GenomeType::Chloroplast => Ok("plant-plastid"),
+        GenomeType::None => bail!("There are no presets without an organelle."),
+    }
+}
+
+/// Look up a preset by name.
+///
+/// Entries in the preset file (a TOML file of tables named by preset, e.g.
+/// `[vertebrate-mito]`, with any of the fields `size_lower`, `size_upper`,
+/// `gc_lower`, `gc_upper` and `size_slack`) override the built in presets of
+/// the same name, or add new ones.
+pub fn get_preset(
+    name: &str,
+    genome_type: GenomeType,
+    preset_file: Option<&PathBuf>,
+) -> Result<Preset> {
+    let builtin = PRESETS
+        .iter()
+        .find(|(n, g, _)| *n == name && *g == genome_type)
+        .map(|(_, _, p)| *p);
+
+    let mut entries: HashMap<String, PresetEntry> = match preset_file {
+        Some(f) => {
+            let contents = fs::read_to_string(f)
+                .with_context(|| format!("Could not read preset file: {:?}", f.as_os_str()))?;
+            toml::from_str(&contents)
+                .with_context(|| format!("Could not parse preset file: {:?}", f.as_os_str()))?
+        }
+        None => HashMap::new(),
+    };
+
+    match (entries.remove(name), builtin) {
+        (Some(entry), Some(base)) => Ok(entry.over(base)),
+        (Some(entry), None) => entry.complete(name),
+        (None, Some(base)) => Ok(base),
+        (None, None) => {
+            let available = PRESETS
+                .iter()
+                .filter(|(_, g, _)| *g == genome_type)
+                .map(|(n, _, _)| *n)
+                .chain(entries.keys().map(|k| k.as_str()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            bail!(
+                "No preset named {}. Available presets: {}.",
+                name,
+                available
+            )
+        }
+    }
+}
+
+/// Resolve the organelle bounds from the CLI.
+///
+/// The `--preset` (or the default preset for the organelle) is looked up, and then
+/// any bounds given on the command line take precedence.
+pub fn organelle_preset(matches: &clap::ArgMatches, genome_type: GenomeType) -> Result<Preset> {
+    let name = match matches.get_one::<String>("preset") {
+        Some(n) => n.as_str(),
+        None => default_preset_name(genome_type)?,
+    };
+    let preset_file = matches.get_one::<PathBuf>("preset-file");
+
+    let mut preset = get_preset(name, genome_type, preset_file)?;
+
+    if let Some(size_lower) = matches.get_one::<usize>("size-lower") {
+        preset.size_lower = *size_lower;
+    }
+    if let Some(size_upper) = matches.get_one::<usize>("size-upper") {
+        preset.size_upper = *size_upper;
+    }
+    if let Some(gc_lower) = matches.get_one::<f32>("gc-lower") {
+        preset.gc_lower = *gc_lower;
+    }
+    if let Some(gc_upper) = matches.get_one::<f32>("gc-upper") {
+        preset.gc_upper = *gc_upper;
+    }
+    if let Some(size_slack) = matches.get_one::<usize>("size-slack") {
+        preset.size_slack = *size_slack;
+    }
+
+    Ok(preset)
+}
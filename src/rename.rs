@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use crate::gfa::graph::{sort_gfa, SortOrder};
 use crate::load::{load_gfa, load_gfa_stdin};
 use crate::utils;
 use anyhow::{bail, Result};
@@ -28,6 +29,8 @@ pub fn rename_gfa(matches: &clap::ArgMatches) -> Result<()> {
         },
     };
 
+    // segments are numbered in order
+    let gfa = sort_gfa(&gfa, SortOrder::from_matches(matches));
     let name_map = NameMap::build_from_gfa(&gfa);
 
     if let Some(new_gfa) = name_map.gfa_bytestring_to_usize(&gfa, false) {
@@ -0,0 +1,210 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::gfa::gfa::GFAtk;
+use crate::load::{load_gfa, load_gfa_stdin};
+use crate::utils;
+use anyhow::{bail, Result};
+use gfa::gfa::Orientation;
+
+/// The properties of a single segment in a GFA.
+#[derive(Clone, Debug)]
+pub struct SegmentStat {
+    /// The segment name.
+    pub name: Vec<u8>,
+    /// The sequence length.
+    pub length: usize,
+    /// The GC% of the sequence.
+    pub gc: f32,
+    /// The coverage (`ll` tag).
+    pub coverage: f32,
+    /// The length weighted median segment coverage of its component.
+    pub component_coverage: f32,
+    /// The coverage relative to the length weighted median coverage of its component,
+    /// or `None` if that median is 0.
+    pub copy_number: Option<f32>,
+    /// Index of the weakly connected component, as in `gfatk stats`.
+    pub component: usize,
+    /// The number of links on the start of the segment (in forward orientation).
+    pub start_links: usize,
+    /// The number of links on the end of the segment (in forward orientation).
+    pub end_links: usize,
+}
+
+impl SegmentStat {
+    /// A tip is a segment with links on one end only.
+    pub fn is_tip(&self) -> bool {
+        (self.start_links == 0) != (self.end_links == 0)
+    }
+}
+
+/// Compute the [`SegmentStat`] of every segment in a GFA, in GFA order.
+pub fn segment_stats(gfa: &GFAtk) -> Result<Vec<SegmentStat>> {
+    let (graph_indices, gfa_graph) = gfa.into_digraph()?;
+    let components = gfa_graph.weakly_connected_components(graph_indices)?;
+
+    let mut component_of = HashMap::new();
+    for (index, component) in components.iter().enumerate() {
+        for segment in component {
+            component_of.insert(segment.as_slice(), index);
+        }
+    }
+
+    // links on the start and end of each segment
+    let mut end_links: HashMap<&[u8], (usize, usize)> = HashMap::new();
+    for link in gfa.unique_links() {
+        let from = end_links.entry(&link.from_segment).or_default();
+        match link.from_orient {
+            Orientation::Forward => from.1 += 1,
+            Orientation::Backward => from.0 += 1,
+        }
+        let to = end_links.entry(&link.to_segment).or_default();
+        match link.to_orient {
+            Orientation::Forward => to.0 += 1,
+            Orientation::Backward => to.1 += 1,
+        }
+    }
+
+    let mut len_cov = Vec::new();
+    for segment in &gfa.0.segments {
+        let (length, coverage) = gfa.node_seq_len_and_cov(segment.name.clone())?;
+        len_cov.push((length, coverage));
+    }
+
+    // the single copy coverage of each component
+    let mut component_coverage = Vec::new();
+    for index in 0..components.len() {
+        let values: Vec<(f32, usize)> = gfa
+            .0
+            .segments
+            .iter()
+            .zip(&len_cov)
+            .filter(|(s, _)| component_of.get(s.name.as_slice()) == Some(&index))
+            .map(|(_, (length, coverage))| (*coverage, *length))
+            .collect();
+        component_coverage.push(utils::weighted_median(&values).unwrap_or(0.0));
+    }
+
+    let mut stats = Vec::new();
+    for (segment, (length, coverage)) in gfa.0.segments.iter().zip(len_cov) {
+        let component = *component_of
+            .get(segment.name.as_slice())
+            .unwrap_or(&components.len());
+        let (start_links, end_links) = end_links
+            .get(segment.name.as_slice())
+            .copied()
+            .unwrap_or((0, 0));
+
+        let single_copy = component_coverage
+            .get(component)
+            .copied()
+            .unwrap_or(coverage);
+
+        stats.push(SegmentStat {
+            name: segment.name.clone(),
+            length,
+            gc: utils::gc_content(&segment.sequence),
+            coverage,
+            component_coverage: single_copy,
+            copy_number: (single_copy > 0.0).then(|| coverage / single_copy),
+            component,
+            start_links,
+            end_links,
+        });
+    }
+
+    Ok(stats)
+}
+
+/// Print a table of per-segment statistics from a GFA.
+///
+/// Segments can be filtered on length, coverage, component and tips,
+/// and sorted on any numeric column, or name. The copy number is `NA` where the
+/// median coverage of the component is 0.
+///
+/// For example:
+/// ```bash
+/// gfatk segments in.gfa --min-length 1000 --sort coverage --descending > segments.tsv
+/// ```
+pub fn segments(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let min_length = matches.get_one::<usize>("min-length");
+    let max_length = matches.get_one::<usize>("max-length");
+    let min_coverage = matches.get_one::<f32>("min-coverage");
+    let max_coverage = matches.get_one::<f32>("max-coverage");
+    let component = matches.get_one::<usize>("component");
+    let tips = matches.get_flag("tips");
+    let sort = matches.get_one::<String>("sort");
+    let descending = matches.get_flag("descending");
+
+    let gfa: GFAtk = match gfa_file {
+        Some(f) => {
+            let ext = f.extension();
+            match ext {
+                Some(e) => {
+                    if e == "gfa" {
+                        GFAtk(load_gfa(f)?)
+                    } else {
+                        bail!("Input is not a GFA.")
+                    }
+                }
+                None => bail!("Could not read file."),
+            }
+        }
+        None => match utils::is_stdin() {
+            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            false => bail!("No input from STDIN. Run `gfatk segments -h` for help."),
+        },
+    };
+
+    let gfa = gfa.sorted(matches);
+
+    let mut stats: Vec<SegmentStat> = segment_stats(&gfa)?
+        .into_iter()
+        .filter(|s| min_length.is_none_or(|l| s.length >= *l))
+        .filter(|s| max_length.is_none_or(|l| s.length <= *l))
+        .filter(|s| min_coverage.is_none_or(|c| s.coverage >= *c))
+        .filter(|s| max_coverage.is_none_or(|c| s.coverage <= *c))
+        .filter(|s| component.is_none_or(|c| s.component == *c))
+        .filter(|s| !tips || s.is_tip())
+        .collect();
+
+    if let Some(sort) = sort {
+        let float_cmp = |a: f32, b: f32| a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+        stats.sort_by(|a, b| match sort.as_str() {
+            "length" => a.length.cmp(&b.length),
+            "gc" => float_cmp(a.gc, b.gc),
+            "coverage" => float_cmp(a.coverage, b.coverage),
+            "copy-number" => a
+                .copy_number
+                .partial_cmp(&b.copy_number)
+                .unwrap_or(Ordering::Equal),
+            "component" => a.component.cmp(&b.component),
+            _ => utils::natural_cmp(&a.name, &b.name),
+        });
+    }
+    if descending {
+        stats.reverse();
+    }
+
+    println!("name\tlength\tgc\tcoverage\tcopy_number\tcomponent\tstart_links\tend_links\ttip");
+
+    for stat in stats {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            String::from_utf8_lossy(&stat.name),
+            stat.length,
+            stat.gc,
+            stat.coverage,
+            stat.copy_number
+                .map_or("NA".to_string(), |c| format!("{:.2}", c)),
+            stat.component,
+            stat.start_links,
+            stat.end_links,
+            stat.is_tip()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+
+use crate::gfa::gfa::GFAtk;
+use crate::load::{load_gfa, load_gfa_stdin};
+use crate::utils;
+use anyhow::{bail, Context, Result};
+use gfa::gfa::Orientation;
+
+/// Parse an oriented segment, e.g. `12+`.
+fn parse_oriented(segment: &str) -> Result<(Vec<u8>, Orientation)> {
+    let orientation = match segment.chars().last() {
+        Some('+') => Orientation::Forward,
+        Some('-') => Orientation::Backward,
+        _ => bail!(
+            "Segment {} should end in its orientation, + or -. E.g. 12+",
+            segment
+        ),
+    };
+    Ok((
+        segment.as_bytes()[..segment.len() - 1].to_vec(),
+        orientation,
+    ))
+}
+
+/// Find the shortest walk (in bp) between two oriented segments of a GFA.
+///
+/// Walks must be orientation-consistent, and the length of a walk counts each
+/// overlap once. Prints a TSV of the walk, or the `--k` shortest, with their lengths.
+/// The walks are in the notation of `gfatk path`.
+///
+/// For example:
+/// ```bash
+/// gfatk shortest-path 12+ 4- in.gfa -k 3
+/// ```
+pub fn shortest_path(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let from = parse_oriented(matches.get_one::<String>("FROM").expect("required by clap"))?;
+    let to = parse_oriented(matches.get_one::<String>("TO").expect("required by clap"))?;
+    let k = *matches.get_one::<usize>("k").expect("defaulted by clap");
+
+    let gfa: GFAtk = match gfa_file {
+        Some(f) => {
+            let ext = f.extension();
+            match ext {
+                Some(e) => {
+                    if e == "gfa" {
+                        GFAtk(load_gfa(f)?)
+                    } else {
+                        bail!("Input is not a GFA.")
+                    }
+                }
+                None => bail!("Could not read file."),
+            }
+        }
+        None => match utils::is_stdin() {
+            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            false => bail!("No input from STDIN. Run `gfatk shortest-path -h` for help."),
+        },
+    };
+
+    let (graph_indices, gfa_graph) = gfa.into_digraph()?;
+    let overlaps = gfa.link_overlaps()?;
+    let lengths = gfa_graph
+        .0
+        .node_indices()
+        .map(|node| Ok(gfa.node_seq_len_and_cov(gfa_graph.0[node].clone())?.0))
+        .collect::<Result<Vec<usize>>>()?;
+
+    let oriented_node = |(name, orientation): (Vec<u8>, Orientation)| -> Result<_> {
+        let node = graph_indices
+            .seg_id_to_node_index(name.clone())
+            .with_context(|| {
+                format!(
+                    "Segment {} not found in GFA.",
+                    String::from_utf8_lossy(&name)
+                )
+            })?;
+        Ok((node, orientation))
+    };
+    let (from, to) = (oriented_node(from)?, oriented_node(to)?);
+
+    let walks = gfa_graph.shortest_walks(from, to, &lengths, &overlaps, k);
+    if walks.is_empty() {
+        bail!("No walk found between the segments.");
+    }
+
+    eprintln!("[+]\tFound {} walk(s).", walks.len());
+
+    println!("rank\tlength\tsegments\tpath");
+    for (rank, (walk, length)) in walks.iter().enumerate() {
+        let path = walk
+            .iter()
+            .map(|(node, orientation)| {
+                Ok(format!(
+                    "{}{}",
+                    String::from_utf8_lossy(&graph_indices.node_index_to_seg_id(*node)?),
+                    orientation
+                ))
+            })
+            .collect::<Result<Vec<String>>>()?
+            .join(",");
+        println!("{}\t{}\t{}\t{}", rank + 1, length, walk.len(), path);
+    }
+
+    Ok(())
+}
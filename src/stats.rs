@@ -1,10 +1,14 @@
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+use crate::gfa::gfa::{Circularity, TopologyStats};
 use crate::load::load_gfa;
+use crate::markers::Markers;
+use crate::preset::{get_preset, organelle_preset, Preset};
 use crate::utils::{self, GFAGraphLookups};
 use crate::{gfa::gfa::GFAtk, gfa::graph::segments_subgraph, load::load_gfa_stdin};
 use anyhow::{bail, Result};
-use petgraph::algo::is_cyclic_directed;
+use serde::{Serialize, Serializer};
 
 /// Enumeration of the genomes we are interested in.
 #[derive(PartialEq, Clone, Copy)]
@@ -17,28 +21,104 @@ pub enum GenomeType {
     None,
 }
 
+/// The output formats of `gfatk stats`.
+#[derive(PartialEq, Clone, Copy)]
+pub enum StatsFormat {
+    /// Indented prose, one block per subgraph.
+    Text,
+    /// A TSV from [`Stats::print_tabular`].
+    Tsv,
+    /// A JSON array from [`Stats::print_json`].
+    Json,
+    /// One JSON object per line from [`Stats::print_json`].
+    JsonLines,
+}
+
 /// The statistics associated with a subgraph in a GFA.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct Stat {
     /// Arbitrary index of the subgraph(s).
     pub index: usize,
-    /// The average GC% across a subgraph.
+    /// The GC% across a subgraph, weighted by segment length and minus overlaps.
     pub gc: f32,
     /// The node count of the graph.
     pub node_count: usize,
     /// The edge count of the graph.
     pub edge_count: usize,
     /// The segments of the (sub)graph.
+    #[serde(skip)]
     pub graph_indices_subgraph: GFAGraphLookups,
-    /// The average coverage across a subgraph.
+    /// The coverage across a subgraph, weighted by segment length and minus overlaps.
     pub cov: f32,
     /// Names of the segments.
+    #[serde(serialize_with = "serialize_segments")]
     pub segments: Vec<Vec<u8>>,
     /// Total sequence length of all the segments.
     pub total_sequence_length: usize,
-    /// Whether the subgraph is circular
-    /// (only applies to mitochondrial genomes).
-    pub is_circular: bool,
+    /// Total sequence length of all the segments, minus the overlaps between them.
+    pub corrected_sequence_length: usize,
+    /// Whether the subgraph is circular, linear or complex.
+    pub circularity: Circularity,
+    /// The topology of the subgraph.
+    #[serde(flatten)]
+    pub topology: TopologyStats,
+}
+
+/// The evidence that a subgraph in a GFA is a particular organelle.
+#[derive(Clone, Debug)]
+pub struct OrganelleScore {
+    /// Index of the subgraph, as in [`Stat`].
+    pub index: usize,
+    /// Whether the GC% of the subgraph is within bounds.
+    pub gc_in_range: bool,
+    /// Whether the total sequence length of the subgraph is within bounds.
+    pub size_in_range: bool,
+    /// The number of marker genes found in the subgraph.
+    pub markers_found: usize,
+    /// The mean containment of the marker genes in the subgraph.
+    pub marker_containment: f32,
+    /// The combined score, between 0 and 1.
+    pub score: f32,
+}
+
+/// Serialize segment names as strings, rather than arrays of bytes.
+fn serialize_segments<S: Serializer>(
+    segments: &[Vec<u8>],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(segments.iter().map(|s| String::from_utf8_lossy(s)))
+}
+
+/// Format the end degree distribution as `degree:count` pairs, e.g. `0:2,1:4`.
+fn end_degree_string(end_degrees: &BTreeMap<usize, usize>) -> String {
+    end_degrees
+        .iter()
+        .map(|(degree, count)| format!("{}:{}", degree, count))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Whether a subgraph would be selected as an organelle by [`Stats::extract_organelle`],
+/// given a named preset.
+#[derive(Clone, Debug, Serialize)]
+pub struct OrganelleCall {
+    /// The name of the preset used.
+    pub preset: String,
+    /// Whether the GC% of the subgraph is within bounds.
+    pub gc_in_range: bool,
+    /// Whether the sequence length (minus overlaps) of the subgraph is within bounds.
+    pub size_in_range: bool,
+    /// Whether the subgraph would be extracted.
+    pub selected: bool,
+}
+
+/// A [`Stat`] with its organelle calls, as serialized by [`Stats::print_json`].
+#[derive(Serialize)]
+struct StatRecord<'a> {
+    #[serde(flatten)]
+    stat: &'a Stat,
+    mitochondria: OrganelleCall,
+    plastid: OrganelleCall,
 }
 
 /// A vector of `Stat`.
@@ -61,7 +141,17 @@ impl Stats {
             "coverage",
             "segments",
             "total_seq_len",
-            "is_circular",
+            "corrected_seq_len",
+            "circularity",
+            "n50",
+            "l50",
+            "largest_segment",
+            "end_degrees",
+            "dead_ends",
+            "self_loops",
+            "hairpins",
+            "simple_bubbles",
+            "repeat_nodes",
         ];
         // print headers
         println!("{}", headers.join("\t"));
@@ -75,7 +165,9 @@ impl Stats {
             cov,
             segments,
             total_sequence_length,
-            is_circular,
+            corrected_sequence_length,
+            circularity,
+            topology,
         } in &self.0
         {
             let segment_string = segments
@@ -85,7 +177,7 @@ impl Stats {
                 .join(",");
 
             println!(
-                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
                 index,
                 gc,
                 node_count,
@@ -93,36 +185,83 @@ impl Stats {
                 cov,
                 segment_string,
                 total_sequence_length,
-                is_circular
+                corrected_sequence_length,
+                circularity,
+                topology.n50,
+                topology.l50,
+                topology.largest_segment,
+                end_degree_string(&topology.end_degrees),
+                topology.dead_ends,
+                topology.self_loops,
+                topology.hairpins,
+                topology.simple_bubbles,
+                topology.repeat_nodes
             );
         }
     }
 
-    /// Extract the putative mitochondrial/chloroplast genome from a GFA
-    /// file.
+    /// Print [`Stats`] to STDOUT as JSON, with the organelle calls of each subgraph
+    /// against a mitochondrial and a plastid preset, each given with its name.
     ///
-    /// The upper and lower limits of genome size and GC content are supplied through the
-    /// CLI. As the defaults will be different, the same function is accessed entry points
-    /// in the CLI.
-
-    pub fn extract_organelle(
-        &mut self,
-        size_lower: usize,
-        mut size_upper: usize,
-        gc_lower: f32,
-        gc_upper: f32,
-    ) -> Result<Vec<Vec<u8>>> {
-        // just going to hard code these for the moment
-        // these values are taken from GoaT
-        // these values are within 2 stddevs of the mean,
-        // so most chloroplasts should pop out
-
-        // adjust because of overlaps between segments
-        // kind of arbitrary...
-        let seq_len_adj = 20000;
-        size_upper += seq_len_adj;
-
-        let stat_vec = &mut self.0;
+    /// If `lines` is true, one JSON object is printed per line (JSON Lines), otherwise
+    /// a single JSON array.
+    pub fn print_json(
+        &self,
+        mito: (&str, &Preset),
+        plastid: (&str, &Preset),
+        lines: bool,
+    ) -> Result<()> {
+        let call = |stat: &Stat, (name, preset): (&str, &Preset)| {
+            let (gc_in_range, size_in_range) = Self::in_organelle_bounds(stat, preset);
+            OrganelleCall {
+                preset: name.to_string(),
+                gc_in_range,
+                size_in_range,
+                selected: gc_in_range && size_in_range,
+            }
+        };
+
+        let records: Vec<StatRecord> = self
+            .0
+            .iter()
+            .map(|stat| StatRecord {
+                stat,
+                mitochondria: call(stat, mito),
+                plastid: call(stat, plastid),
+            })
+            .collect();
+
+        if lines {
+            for record in &records {
+                println!("{}", serde_json::to_string(record)?);
+            }
+        } else {
+            println!("{}", serde_json::to_string_pretty(&records)?);
+        }
+
+        Ok(())
+    }
+
+    /// Whether the GC content and the sequence length (minus overlaps) of a [`Stat`]
+    /// fall within the bounds of a [`Preset`], in that order.
+    pub fn in_organelle_bounds(stat: &Stat, preset: &Preset) -> (bool, bool) {
+        let size_upper = preset.size_upper + preset.size_slack;
+
+        (
+            stat.gc > preset.gc_lower && stat.gc < preset.gc_upper,
+            stat.corrected_sequence_length > preset.size_lower
+                && stat.corrected_sequence_length < size_upper,
+        )
+    }
+
+    /// Filter the [`Stats`] to those subgraphs whose sequence length (minus overlaps) and GC content
+    /// fall within the bounds of a [`Preset`].
+    ///
+    /// The upper and lower limits of genome size and GC content come from a preset,
+    /// which can be overridden through the CLI. As the defaults will be different,
+    /// the same function is accessed by the entry points in the CLI.
+    pub fn filter_organelle(&self, preset: &Preset) -> Result<Vec<&Stat>> {
+        let stat_vec = &self.0;
         let stat_vec_len = stat_vec.len();
         // filter this vector to have stats in line with the span/gc
 
@@ -130,39 +269,153 @@ impl Stats {
             // apply the filter
             let stat_vec: Vec<&Stat> = stat_vec
                 .iter()
-                .filter(
-                    |Stat {
-                         index: _,
-                         gc,
-                         cov: _,
-                         segments: _,
-                         total_sequence_length,
-                         is_circular: _,
-                         node_count: _,
-                         edge_count: _,
-                         graph_indices_subgraph: _,
-                     }| {
-                        (gc > &gc_lower && gc < &gc_upper)
-                            && (total_sequence_length > &size_lower
-                                && total_sequence_length < &size_upper)
-                    },
-                )
+                .filter(|stat| {
+                    let (gc_in_range, size_in_range) = Self::in_organelle_bounds(stat, preset);
+                    gc_in_range && size_in_range
+                })
                 .collect();
-            // let's return all the filtered segments
+            // let's return all the filtered subgraphs
             // and see if it works for now
             match stat_vec.len() {
-                0 => bail!("No subgraphs within the bounds:\nsize_upper: {size_upper}\nsize_lower: {size_lower}\ngc_upper: {gc_upper}\ngc_lower: {gc_lower}\nTry changing limits?"),
-                1.. => {
-                    // extract all segments
-                    let segments = stat_vec.iter().flat_map(|Stat { segments, .. }| segments.clone()).collect();
-                    Ok(segments)
-                },
-                _ => unreachable!()
+                0 => bail!(
+                    "No subgraphs within the bounds:\nsize_upper: {} (+ {} slack)\nsize_lower: {}\ngc_upper: {}\ngc_lower: {}\nTry changing limits, or another --preset?",
+                    preset.size_upper,
+                    preset.size_slack,
+                    preset.size_lower,
+                    preset.gc_upper,
+                    preset.gc_lower
+                ),
+                1.. => Ok(stat_vec),
             }
         } else {
             bail!("There were no segments to be extracted. Check input GFA file.");
         }
     }
+
+    /// Score each subgraph as a putative organelle, combining the GC/size bounds of
+    /// [`Stats::filter_organelle`] with the containment of marker genes.
+    ///
+    /// A subgraph scores 0.25 each for GC and size within bounds, and 0.5 if at least
+    /// one marker has a k-mer containment of `min_containment` or more. This lets
+    /// fragmented organelle subgraphs carrying markers through, while keeping out
+    /// nuclear subgraphs which happen to fall within the bounds.
+    pub fn score_organelle(
+        &self,
+        gfa: &GFAtk,
+        preset: &Preset,
+        markers: &Markers,
+        min_containment: f32,
+    ) -> Result<Vec<OrganelleScore>> {
+        let mut scores = Vec::new();
+
+        for stat in &self.0 {
+            let (gc_in_range, size_in_range) = Self::in_organelle_bounds(stat, preset);
+
+            let subgraph = segments_subgraph(&gfa.0, stat.segments.clone());
+            let containment =
+                markers.containment(subgraph.segments.iter().map(|s| s.sequence.as_slice()));
+            let markers_found = containment
+                .iter()
+                .filter(|c| **c >= min_containment)
+                .count();
+            let marker_containment = containment.iter().sum::<f32>() / containment.len() as f32;
+
+            let score = 0.25 * gc_in_range as u8 as f32
+                + 0.25 * size_in_range as u8 as f32
+                + 0.5 * (markers_found > 0) as u8 as f32;
+
+            scores.push(OrganelleScore {
+                index: stat.index,
+                gc_in_range,
+                size_in_range,
+                markers_found,
+                marker_containment,
+                score,
+            });
+        }
+
+        Ok(scores)
+    }
+
+    /// Extract the putative mitochondrial/chloroplast genome from a GFA
+    /// file.
+    ///
+    /// Returns the segments of all the subgraphs passing [`Stats::filter_organelle`].
+    pub fn extract_organelle(&self, preset: &Preset) -> Result<Vec<Vec<u8>>> {
+        let stat_vec = self.filter_organelle(preset)?;
+        // extract all segments
+        let segments = stat_vec
+            .iter()
+            .flat_map(|Stat { segments, .. }| segments.clone())
+            .collect();
+        Ok(segments)
+    }
+}
+
+/// Compute a [`Stat`] for each weakly connected component (subgraph) of a GFA.
+///
+/// If `tabular` is false and `genome_type` is [`GenomeType::None`], the statistics
+/// of each subgraph are printed to STDOUT as they are computed.
+pub fn subgraph_stats(gfa: &GFAtk, genome_type: GenomeType, tabular: bool) -> Result<Stats> {
+    // load gfa into graph structure
+    let (graph_indices, gfa_graph) = gfa.into_digraph()?;
+
+    let subgraphs = gfa_graph.weakly_connected_components(graph_indices)?;
+
+    let mut store_stats = Stats(Vec::new());
+
+    for (no_subgraphs, id_set) in subgraphs.iter().enumerate() {
+        let subgraph_gfa = GFAtk(segments_subgraph(&gfa.0, id_set.to_vec()));
+
+        let (graph_indices_subgraph, subgraph) = subgraph_gfa.into_digraph()?;
+
+        // we want to see if the subgraph is circular.
+        let circularity = subgraph_gfa.circularity();
+
+        // print stats
+        if !tabular && genome_type == GenomeType::None {
+            println!("Subgraph {}:", no_subgraphs + 1);
+            println!("\tNumber of nodes/segments: {}", subgraph.node_count());
+            println!("\tNumber of edges/links: {}", subgraph.edge_count());
+            println!("\tCircularity: {}", circularity);
+            // equivalent to id_set
+            println!("{}", graph_indices_subgraph);
+        }
+
+        let sequence_stats = subgraph_gfa.sequence_stats(genome_type, tabular)?;
+        let topology = subgraph_gfa.topology_stats()?;
+
+        if !tabular && genome_type == GenomeType::None {
+            println!("\tSegment N50:\t{}", topology.n50);
+            println!("\tSegment L50:\t{}", topology.l50);
+            println!("\tLargest segment:\t{}", topology.largest_segment);
+            println!(
+                "\tLinks per segment end (degree:count):\t{}",
+                end_degree_string(&topology.end_degrees)
+            );
+            println!("\tDead ends:\t{}", topology.dead_ends);
+            println!("\tSelf loops:\t{}", topology.self_loops);
+            println!("\tHairpins:\t{}", topology.hairpins);
+            println!("\tSimple bubbles:\t{}", topology.simple_bubbles);
+            println!("\tRepeat segments:\t{}", topology.repeat_nodes);
+        }
+
+        store_stats.push(Stat {
+            index: no_subgraphs,
+            node_count: subgraph.node_count(),
+            edge_count: subgraph.edge_count(),
+            graph_indices_subgraph,
+            gc: sequence_stats.gc,
+            cov: sequence_stats.coverage,
+            segments: id_set.clone(),
+            total_sequence_length: sequence_stats.sequence_length,
+            corrected_sequence_length: sequence_stats.corrected_sequence_length,
+            circularity,
+            topology,
+        });
+    }
+
+    Ok(store_stats)
 }
 
 // I've handled 'further' here really badly...
@@ -182,42 +435,77 @@ pub fn stats(
 ) -> Result<Option<(GFAtk, Vec<Vec<u8>>)>> {
     let gfa_file = matches.get_one::<PathBuf>("GFA");
     let tabular = matches.get_flag("tabular");
-    // only passed through extract_mito
-    let mito_args = if matches!(genome_type, GenomeType::Mitochondria) {
-        let size_lower = *matches
-            .get_one::<usize>("size-lower")
-            .expect("defaulted by clap");
-        let size_upper = *matches
-            .get_one::<usize>("size-upper")
-            .expect("defaulted by clap");
-        let gc_lower = *matches
-            .get_one::<f32>("gc-lower")
-            .expect("defaulted by clap");
-        let gc_upper = *matches
-            .get_one::<f32>("gc-upper")
-            .expect("defaulted by clap");
-        Some((size_lower, size_upper, gc_lower, gc_upper))
-    } else {
-        None
+    // only passed through `gfatk stats`
+    let format = match genome_type {
+        GenomeType::None if !tabular => {
+            match matches
+                .get_one::<String>("format")
+                .expect("defaulted by clap")
+                .as_str()
+            {
+                "tsv" => StatsFormat::Tsv,
+                "json" => StatsFormat::Json,
+                "jsonl" => StatsFormat::JsonLines,
+                _ => StatsFormat::Text,
+            }
+        }
+        _ if tabular => StatsFormat::Tsv,
+        _ => StatsFormat::Text,
+    };
+    // the presets the subgraphs are called against in the JSON output
+    let json_presets = match format {
+        StatsFormat::Json | StatsFormat::JsonLines => {
+            let preset_file = matches.get_one::<PathBuf>("preset-file");
+            let mito_name = matches
+                .get_one::<String>("mito-preset")
+                .expect("defaulted by clap");
+            let plastid_name = matches
+                .get_one::<String>("plastid-preset")
+                .expect("defaulted by clap");
+            Some((
+                (
+                    mito_name.as_str(),
+                    get_preset(mito_name, GenomeType::Mitochondria, preset_file)?,
+                ),
+                (
+                    plastid_name.as_str(),
+                    get_preset(plastid_name, GenomeType::Chloroplast, preset_file)?,
+                ),
+            ))
+        }
+        _ => None,
     };
-    // only required for extract_chloro
-    let chloro_args = if matches!(genome_type, GenomeType::Chloroplast) {
-        let size_lower = *matches
-            .get_one::<usize>("size-lower")
-            .expect("defaulted by clap");
-        let size_upper = *matches
-            .get_one::<usize>("size-upper")
-            .expect("defaulted by clap");
-        let gc_lower = *matches
-            .get_one::<f32>("gc-lower")
-            .expect("defaulted by clap");
-        let gc_upper = *matches
-            .get_one::<f32>("gc-upper")
-            .expect("defaulted by clap");
-        Some((size_lower, size_upper, gc_lower, gc_upper))
-    } else {
-        None
+    // only passed through extract_mito and extract_chloro
+    let preset = match genome_type {
+        GenomeType::None => None,
+        _ => Some(organelle_preset(matches, genome_type)?),
     };
+    // marker genes are optional for both extract_mito and extract_chloro
+    let marker_args = match genome_type {
+        GenomeType::None => None,
+        _ => match matches.get_one::<PathBuf>("markers") {
+            Some(m) => {
+                let kmer_size = *matches
+                    .get_one::<usize>("kmer-size")
+                    .expect("defaulted by clap");
+                let min_containment = *matches
+                    .get_one::<f32>("min-containment")
+                    .expect("defaulted by clap");
+                let min_score = *matches
+                    .get_one::<f32>("min-score")
+                    .expect("defaulted by clap");
+                Some((
+                    Markers::from_fasta(m, kmer_size)?,
+                    min_containment,
+                    min_score,
+                ))
+            }
+            None => None,
+        },
+    };
+
+    // only passed through `gfatk stats`, where the GFA is not returned
+    let normalize_links = genome_type == GenomeType::None && matches.get_flag("normalize-links");
 
     let gfa = match gfa_file {
         Some(f) => {
@@ -225,7 +513,7 @@ pub fn stats(
             match ext {
                 Some(e) => {
                     if e == "gfa" {
-                        GFAtk(load_gfa(f)?)
+                        GFAtk::new(load_gfa(f)?, normalize_links)?
                     } else {
                         bail!("Input is not a GFA.")
                     }
@@ -234,7 +522,7 @@ pub fn stats(
             }
         }
         None => match utils::is_stdin() {
-            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            true => GFAtk::new(load_gfa_stdin(std::io::stdin().lock())?, normalize_links)?,
             false => bail!(
                 "No input from STDIN. Run `gfatk {} -h` for help.",
                 match genome_type {
@@ -246,89 +534,60 @@ pub fn stats(
         },
     };
 
-    // load gfa into graph structure
-    let (graph_indices, gfa_graph) = gfa.into_digraph()?;
+    let gfa = gfa.sorted(matches);
 
-    let subgraphs = gfa_graph.weakly_connected_components(graph_indices)?;
-
-    let mut no_subgraphs = 0;
-    let mut store_stats = Stats(Vec::new());
-
-    for id_set in &subgraphs {
-        let subgraph_gfa = GFAtk(segments_subgraph(&gfa.0, id_set.to_vec()));
-
-        let (graph_indices_subgraph, subgraph) = subgraph_gfa.into_digraph()?;
+    let store_stats = subgraph_stats(&gfa, genome_type, format != StatsFormat::Text)?;
 
-        // we want to see if the subgraph is circular.
-        let is_circular = is_cyclic_directed(&subgraph.0);
-
-        // print stats
-        if !tabular && genome_type == GenomeType::None {
-            println!("Subgraph {}:", no_subgraphs + 1);
-            println!("\tNumber of nodes/segments: {}", subgraph.node_count());
-            println!("\tNumber of edges/links: {}", subgraph.edge_count());
-            println!("\tCircular: {}", is_circular);
-            // equivalent to id_set
-            println!("{}", graph_indices_subgraph);
-        }
-
-        let (avg_gc, cov, total_sequence_length) =
-            subgraph_gfa.sequence_stats(genome_type, tabular)?;
-
-        store_stats.push(Stat {
-            index: no_subgraphs,
-            node_count: subgraph.node_count(),
-            edge_count: subgraph.edge_count(),
-            graph_indices_subgraph,
-            gc: avg_gc,
-            cov,
-            segments: id_set.clone(),
-            total_sequence_length,
-            is_circular,
-        });
-
-        no_subgraphs += 1;
-    }
-
-    if tabular {
+    match (format, json_presets) {
         // print tabular data
-        store_stats.print_tabular();
+        (StatsFormat::Tsv, _) => store_stats.print_tabular(),
+        (StatsFormat::Json, Some((mito, plastid))) => {
+            store_stats.print_json((mito.0, &mito.1), (plastid.0, &plastid.1), false)?
+        }
+        (StatsFormat::JsonLines, Some((mito, plastid))) => {
+            store_stats.print_json((mito.0, &mito.1), (plastid.0, &plastid.1), true)?
+        }
+        _ => (),
     }
 
     // if we want to do more stat things
-    match genome_type {
-        GenomeType::Mitochondria => {
-            // should be safe to unwrap?
-            let mito_args = mito_args.unwrap();
-            return Ok(Some((
-                gfa,
-                store_stats.extract_organelle(
-                    mito_args.0,
-                    mito_args.1,
-                    mito_args.2,
-                    mito_args.3,
-                )?,
-            )));
-        }
-        GenomeType::Chloroplast => {
-            // safe to unwrap here too.
-            let chloro_args = chloro_args.unwrap();
-            return Ok(Some((
-                gfa,
-                store_stats.extract_organelle(
-                    chloro_args.0,
-                    chloro_args.1,
-                    chloro_args.2,
-                    chloro_args.3,
-                )?,
-            )));
+    let preset = match preset {
+        Some(p) => p,
+        None => {
+            if format == StatsFormat::Text {
+                println!("Total number of subgraphs: {}", store_stats.0.len())
+            }
+            return Ok(None);
         }
-        GenomeType::None => {
-            if !tabular {
-                println!("Total number of subgraphs: {}", no_subgraphs)
+    };
+
+    let segments = match marker_args {
+        Some((markers, min_containment, min_score)) => {
+            let scores = store_stats.score_organelle(&gfa, &preset, &markers, min_containment)?;
+
+            let mut segments = Vec::new();
+            for score in scores {
+                eprintln!(
+                    "[+]\tSubgraph {}: score = {} (gc in range: {}, size in range: {}, markers found: {}/{}, mean marker containment: {})",
+                    score.index,
+                    score.score,
+                    score.gc_in_range,
+                    score.size_in_range,
+                    score.markers_found,
+                    markers.markers.len(),
+                    score.marker_containment
+                );
+                if score.score >= min_score {
+                    segments.extend(store_stats.0[score.index].segments.clone());
+                }
+            }
+            if segments.is_empty() {
+                bail!("No subgraphs scored {min_score} or more. Try changing limits?");
             }
+            segments
         }
-    }
+        None => store_stats.extract_organelle(&preset)?,
+    };
 
-    Ok(None)
+    Ok(Some((gfa, segments)))
 }
@@ -1,21 +1,206 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::path::PathBuf;
 
-use crate::gfa::gfa::GFAtk;
+use crate::compact::compact_gfa;
+use crate::gfa::gfa::{entry_end, exit_end, GFAtk, SegmentEnd};
 use crate::gfa::gfa_string;
-use crate::gfa::graph::segments_subgraph;
+use crate::gfa::graph::{segments_subgraph, SortOrder};
 use crate::load::{load_gfa, load_gfa_stdin};
 use crate::utils;
 use anyhow::{bail, Result};
 
+/// A dead-end branch removed by [`clip_tips`].
+#[derive(Clone, Debug)]
+pub struct ClippedTip {
+    /// The round of clipping the tip was removed in, from 1.
+    pub round: usize,
+    /// The oriented segments of the branch, from the dead end to the junction, e.g. `14+,11+`.
+    pub path: String,
+    /// The segment names in the branch.
+    pub segments: Vec<Vec<u8>>,
+    /// The length of the branch, with overlaps removed.
+    pub length: usize,
+    /// The length weighted coverage of the branch.
+    pub coverage: f32,
+    /// The highest coverage of the segments the branch joins at the junction.
+    pub neighbour_coverage: f32,
+    /// Whether the branch was shorter than the length threshold.
+    pub short: bool,
+    /// Whether the branch was below the coverage fraction of its neighbour.
+    pub low_coverage: bool,
+}
+
+/// Clip dead-end branches (tips) from a GFA.
+///
+/// A tip is a maximal non-branching path (as in `gfatk compact`) with no links on one
+/// end, joined on its other end to a branching point. Tips shorter than `max_length`
+/// (bp), or with coverage below `coverage_fraction` of the segments they join, are
+/// removed, weakest first, leaving at least two branches at each junction. This is
+/// repeated until no more tips are removed. Linear components with two free ends,
+/// and circular components (including single segments linked to themselves), are
+/// never tips. A threshold of zero turns off that test.
+pub fn clip_tips(
+    gfa: &GFAtk,
+    max_length: usize,
+    coverage_fraction: f32,
+) -> Result<(GFAtk, Vec<ClippedTip>)> {
+    let mut current = GFAtk(segments_subgraph(
+        &gfa.0,
+        gfa.0.segments.iter().map(|s| s.name.clone()).collect(),
+    ));
+    let mut clipped = Vec::new();
+    let mut round = 0;
+
+    loop {
+        round += 1;
+        let tips = find_tips(&current, max_length, coverage_fraction, round)?;
+        if tips.is_empty() {
+            break;
+        }
+
+        let removed: HashSet<&Vec<u8>> = tips.iter().flat_map(|t| &t.segments).collect();
+        let keep = current
+            .0
+            .segments
+            .iter()
+            .filter(|s| !removed.contains(&s.name))
+            .map(|s| s.name.clone())
+            .collect();
+        current = GFAtk(segments_subgraph(&current.0, keep));
+        clipped.extend(tips);
+    }
+
+    Ok((current, clipped))
+}
+
+/// A single round of tip finding in [`clip_tips`].
+fn find_tips(
+    gfa: &GFAtk,
+    max_length: usize,
+    coverage_fraction: f32,
+    round: usize,
+) -> Result<Vec<ClippedTip>> {
+    let adjacency = gfa.segment_end_adjacency();
+    let neighbours = |end| adjacency.get(&end).cloned().unwrap_or_default();
+
+    let (_, compacted) = compact_gfa(gfa)?;
+    // candidate tips, and the ends they join at
+    let mut candidates = Vec::new();
+
+    for branch in &compacted {
+        let unitig = &branch.unitig;
+        if unitig.circular {
+            continue;
+        }
+        let (first, first_orientation) = &unitig.segments[0];
+        let (last, last_orientation) = &unitig.segments[unitig.segments.len() - 1];
+        let start = entry_end(first, *first_orientation);
+        let end = exit_end(last, *last_orientation);
+
+        // one free end, and the other joining a branching point
+        let junction = match (neighbours(start).is_empty(), neighbours(end).is_empty()) {
+            (true, false) => end,
+            (false, true) => start,
+            _ => continue,
+        };
+        let joined = neighbours(junction);
+        // a branch joined only to itself is a whole component
+        let own: HashSet<&[u8]> = unitig.segments.iter().map(|(n, _)| n.as_slice()).collect();
+        if joined.iter().all(|(name, _)| own.contains(name)) {
+            continue;
+        }
+        // the ends of the other branches at the junction
+        let mut alternatives: HashSet<SegmentEnd> = joined.iter().copied().collect();
+        for n in &joined {
+            alternatives.extend(neighbours(*n).into_iter().filter(|e| *e != junction));
+        }
+        if alternatives.len() < 2 {
+            continue;
+        }
+
+        let mut neighbour_coverage: f32 = 0.0;
+        for (name, _) in &joined {
+            let (_, coverage) = gfa.node_seq_len_and_cov(name.to_vec())?;
+            neighbour_coverage = neighbour_coverage.max(coverage);
+        }
+
+        let short = branch.length < max_length;
+        let low_coverage = branch.coverage < coverage_fraction * neighbour_coverage;
+        if !short && !low_coverage {
+            continue;
+        }
+
+        // report the branch from its dead end
+        let mut oriented = unitig.clone();
+        if junction == start {
+            oriented.segments = oriented
+                .segments
+                .into_iter()
+                .rev()
+                .map(|(name, orientation)| (name, utils::reverse_orientation(orientation)))
+                .collect();
+        }
+
+        let tip = ClippedTip {
+            round,
+            path: oriented.path_string(),
+            segments: unitig.segments.iter().map(|(n, _)| n.clone()).collect(),
+            length: branch.length,
+            coverage: branch.coverage,
+            neighbour_coverage,
+            short,
+            low_coverage,
+        };
+        candidates.push((tip, junction, alternatives));
+    }
+
+    // clip the weakest tips first, leaving at least two branches at every
+    // junction, so that a component made only of tips keeps a path through it
+    candidates.sort_by(|(a, _, _), (b, _, _)| {
+        a.coverage
+            .partial_cmp(&b.coverage)
+            .unwrap_or(Ordering::Equal)
+            .then(a.length.cmp(&b.length))
+    });
+    let mut clipped_junctions = HashSet::new();
+    let mut tips = Vec::new();
+    for (tip, junction, alternatives) in candidates {
+        let remaining = alternatives
+            .iter()
+            .filter(|e| !clipped_junctions.contains(*e))
+            .count();
+        if remaining >= 2 {
+            clipped_junctions.insert(junction);
+            tips.push(tip);
+        }
+    }
+
+    Ok(tips)
+}
+
 /// Trim a GFA file of segments which are connected only to one other segment.
 ///
+/// With `--tips`, only dead-end branches shorter than `--max-tip-length`, or below
+/// `--tip-coverage` of the segments they join, are clipped instead. `--dry-run` prints
+/// a TSV of the tips that would be clipped, rather than the GFA.
+///
 /// For example:
 /// ```bash
 /// gfatk trim in.gfa > out.gfa
+/// gfatk trim in.gfa --tips --max-tip-length 500 --dry-run > tips.tsv
 /// ```
 pub fn trim(matches: &clap::ArgMatches) -> Result<()> {
     // read in path and parse gfa
     let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let tips = matches.get_flag("tips");
+    let max_tip_length = *matches
+        .get_one::<usize>("max-tip-length")
+        .expect("defaulted by clap");
+    let tip_coverage = *matches
+        .get_one::<f32>("tip-coverage")
+        .expect("defaulted by clap");
+    let dry_run = matches.get_flag("dry-run");
 
     let gfa: GFAtk = match gfa_file {
         Some(f) => {
@@ -33,17 +218,55 @@ pub fn trim(matches: &clap::ArgMatches) -> Result<()> {
         }
         None => match utils::is_stdin() {
             true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
-            false => bail!("No input from STDIN. Run `gfatk extract -h` for help."),
+            false => bail!("No input from STDIN. Run `gfatk trim -h` for help."),
         },
     };
 
-    let (graph_indices, gfa_graph) = gfa.into_digraph()?;
+    if !tips {
+        let (graph_indices, gfa_graph) = gfa.into_digraph()?;
+
+        let trimmed = gfa_graph.trim(graph_indices);
+
+        let subgraph = segments_subgraph(&gfa.0, trimmed);
 
-    let trimmed = gfa_graph.trim(graph_indices);
+        println!(
+            "{}",
+            gfa_string(&subgraph, SortOrder::from_matches(matches))
+        );
+
+        return Ok(());
+    }
+
+    let (clipped_gfa, clipped) = clip_tips(&gfa, max_tip_length, tip_coverage)?;
+
+    if dry_run {
+        println!("round\tpath\tlength\tcoverage\tneighbour_coverage\tshort\tlow_coverage");
+        for tip in &clipped {
+            println!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                tip.round,
+                tip.path,
+                tip.length,
+                tip.coverage,
+                tip.neighbour_coverage,
+                tip.short,
+                tip.low_coverage
+            );
+        }
+        return Ok(());
+    }
 
-    let subgraph = segments_subgraph(&gfa.0, trimmed);
+    for tip in &clipped {
+        eprintln!(
+            "[+]\tClipped tip {} ({}bp, coverage {}) from GFA.",
+            tip.path, tip.length, tip.coverage
+        );
+    }
 
-    println!("{}", gfa_string(&subgraph));
+    println!(
+        "{}",
+        gfa_string(&clipped_gfa.0, SortOrder::from_matches(matches))
+    );
 
     Ok(())
 }
@@ -0,0 +1,365 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::gfa::gfa::{entry_end, exit_end, GFAtk, SegmentEnd};
+use crate::gfa::gfa_string;
+use crate::gfa::graph::SortOrder;
+use crate::load::{load_gfa, load_gfa_stdin};
+use crate::utils;
+use anyhow::{bail, Context, Result};
+use gfa::gfa::{Link, Segment, GFA};
+use gfa::optfields::{OptField, OptFieldVal};
+
+/// A segment with an estimated copy number above one, and what [`unroll_gfa`] did with it.
+#[derive(Clone, Debug)]
+pub struct Repeat {
+    /// The segment name.
+    pub segment: Vec<u8>,
+    /// The segment coverage (`ll`).
+    pub coverage: f32,
+    /// The estimated copy number, from [`GFAtk::gen_cov_hash`].
+    pub copies: usize,
+    /// The names of the copies, if unrolled.
+    pub names: Vec<Vec<u8>>,
+    /// `unrolled`, or why the segment was not.
+    pub outcome: &'static str,
+}
+
+/// The neighbours of a repeat at one of its ends, as the segment ends they link to.
+type Side<'a> = BTreeMap<SegmentEnd<'a>, f32>;
+/// The copies of a repeat linked to each neighbour, keyed by the end of the repeat
+/// (`true` for its end) and the neighbouring segment end.
+type Assignment<'a> = HashMap<(bool, SegmentEnd<'a>), Vec<usize>>;
+
+/// Unroll collapsed repeats, duplicating each segment by its estimated copy number.
+///
+/// The copy number is the coverage relative to the lowest coverage segment (see
+/// [`GFAtk::gen_cov_hash`]), and copies are named `<segment>_<n>`, from 1, each with
+/// an equal share of the coverage. A repeat is unrolled where its links can be
+/// shared between the copies:
+/// - one end links to as many segments as there are copies, one for each copy, and
+///   the other end links to at most one segment, which every copy links to; or
+/// - both ends link to as many segments as there are copies, and the coverages of
+///   the segments at each end differ by more than `tolerance` (as a fraction), so
+///   the highest coverage segment at one end is paired with the highest at the other,
+///   and so on.
+///
+/// Repeats linked to themselves or to another repeat, or with more than
+/// `max_copies`, are left as they are. Path (P) and containment (C) lines through
+/// an unrolled repeat are removed.
+pub fn unroll_gfa(gfa: &GFAtk, max_copies: usize, tolerance: f32) -> Result<(GFAtk, Vec<Repeat>)> {
+    let (graph_indices, _) = gfa.into_digraph()?;
+    let relative_coverage = gfa.gen_cov_hash(&graph_indices)?;
+
+    let mut copies_of: HashMap<&[u8], usize> = HashMap::new();
+    for segment in &gfa.0.segments {
+        let node = graph_indices.seg_id_to_node_index(segment.name.clone())?;
+        copies_of.insert(&segment.name, *relative_coverage.get(&node).unwrap_or(&1));
+    }
+    let coverage = |name: &[u8]| -> Result<f32> { Ok(gfa.node_seq_len_and_cov(name.to_vec())?.1) };
+
+    // the neighbours at the start (false) and end (true) of each segment
+    let adjacency = gfa.segment_end_adjacency();
+    let mut sides: HashMap<SegmentEnd, Side> = HashMap::new();
+    let mut self_linked: HashSet<&[u8]> = HashSet::new();
+    for (end, neighbours) in &adjacency {
+        let side = sides.entry(*end).or_default();
+        for neighbour in neighbours {
+            if neighbour.0 == end.0 {
+                self_linked.insert(end.0);
+            }
+            side.insert(*neighbour, coverage(neighbour.0)?);
+        }
+    }
+
+    let mut repeats = Vec::new();
+    // for each unrolled repeat, and each of its neighbours, the copies linked to it
+    let mut assignments: HashMap<&[u8], Assignment> = HashMap::new();
+
+    for segment in &gfa.0.segments {
+        let copies = copies_of[segment.name.as_slice()];
+        if copies < 2 {
+            continue;
+        }
+        let empty = Side::new();
+        let start = sides
+            .get(&(segment.name.as_slice(), false))
+            .unwrap_or(&empty);
+        let end = sides
+            .get(&(segment.name.as_slice(), true))
+            .unwrap_or(&empty);
+
+        let adjacent_repeat = start
+            .keys()
+            .chain(end.keys())
+            .any(|(name, _)| copies_of.get(name).is_some_and(|c| *c > 1));
+
+        let assignment = if copies > max_copies {
+            Err("too_many_copies")
+        } else if self_linked.contains(segment.name.as_slice()) || adjacent_repeat {
+            Err("adjacent_repeat")
+        } else {
+            assign_copies(start, end, copies, tolerance).ok_or("ambiguous")
+        };
+
+        let mut names = Vec::new();
+        let outcome = match assignment {
+            Ok(assignment) => {
+                for index in 1..=copies {
+                    names.push(
+                        format!("{}_{}", String::from_utf8_lossy(&segment.name), index)
+                            .into_bytes(),
+                    );
+                }
+                assignments.insert(&segment.name, assignment);
+                "unrolled"
+            }
+            Err(reason) => reason,
+        };
+
+        repeats.push(Repeat {
+            segment: segment.name.clone(),
+            coverage: coverage(&segment.name).unwrap_or(0.0),
+            copies,
+            names,
+            outcome,
+        });
+    }
+
+    let mut unrolled: GFA<Vec<u8>, _> = GFA {
+        header: gfa.0.header.clone(),
+        ..GFA::default()
+    };
+    let names: HashSet<&[u8]> = gfa.0.segments.iter().map(|s| s.name.as_slice()).collect();
+    let copy_names: HashMap<&[u8], &Vec<Vec<u8>>> = repeats
+        .iter()
+        .filter(|r| !r.names.is_empty())
+        .map(|r| (r.segment.as_slice(), &r.names))
+        .collect();
+
+    for segment in &gfa.0.segments {
+        let Some(copies) = copy_names.get(segment.name.as_slice()) else {
+            unrolled.segments.push(segment.clone());
+            continue;
+        };
+        // the coverage is shared between the copies
+        let mut optional = segment.optional.clone();
+        for opt in optional.iter_mut() {
+            if opt.tag == *b"ll" {
+                if let OptFieldVal::Float(f) = opt.value {
+                    *opt = OptField::new(b"ll", OptFieldVal::Float(f / copies.len() as f32));
+                }
+            }
+        }
+        for name in copies.iter() {
+            if names.contains(name.as_slice()) {
+                bail!(
+                    "Unrolled segment name {} is already in the GFA.",
+                    String::from_utf8_lossy(name)
+                );
+            }
+            unrolled.segments.push(Segment {
+                name: name.clone(),
+                sequence: segment.sequence.clone(),
+                optional: optional.clone(),
+            });
+        }
+    }
+
+    for link in &gfa.0.links {
+        let from = exit_end(&link.from_segment, link.from_orient);
+        let to = entry_end(&link.to_segment, link.to_orient);
+        // repeats are only unrolled between segments which are not, so at most one
+        // end of a link is on an unrolled repeat
+        let (copies, from_repeat) = match (
+            assignments.get(link.from_segment.as_slice()),
+            assignments.get(link.to_segment.as_slice()),
+        ) {
+            (Some(a), _) => (&a[&(from.1, to)], true),
+            (_, Some(a)) => (&a[&(to.1, from)], false),
+            (None, None) => {
+                unrolled.links.push(link.clone());
+                continue;
+            }
+        };
+        let repeat = if from_repeat {
+            &link.from_segment
+        } else {
+            &link.to_segment
+        };
+        for (index, copy) in copies.iter().enumerate() {
+            let name = copy_names[repeat.as_slice()][*copy].clone();
+            let mut link = match from_repeat {
+                true => Link {
+                    from_segment: name,
+                    ..link.clone()
+                },
+                false => Link {
+                    to_segment: name,
+                    ..link.clone()
+                },
+            };
+            // the edge coverage is shared between the copies linked, spreading the remainder
+            let shares = copies.len() as i64;
+            for opt in link.optional.iter_mut() {
+                if opt.tag == *b"ec" {
+                    if let OptFieldVal::Int(ec) = opt.value {
+                        let share = ec / shares + i64::from((index as i64) < ec % shares);
+                        *opt = OptField::new(b"ec", OptFieldVal::Int(share));
+                    }
+                }
+            }
+            unrolled.links.push(link);
+        }
+    }
+
+    let through_repeat = |name: &[u8]| copy_names.contains_key(name);
+    unrolled.containments = gfa
+        .0
+        .containments
+        .iter()
+        .filter(|c| !through_repeat(&c.container_name) && !through_repeat(&c.contained_name))
+        .cloned()
+        .collect();
+    unrolled.paths = gfa
+        .0
+        .paths
+        .iter()
+        .filter(|p| !p.iter().any(|(s, _)| through_repeat(s.as_ref())))
+        .cloned()
+        .collect();
+
+    Ok((GFAtk(unrolled), repeats))
+}
+
+/// Share the neighbours at the `start` and `end` of a repeat between its copies (from
+/// 0), or `None` if that is ambiguous.
+fn assign_copies<'a>(
+    start: &Side<'a>,
+    end: &Side<'a>,
+    copies: usize,
+    tolerance: f32,
+) -> Option<Assignment<'a>> {
+    // highest coverage first, ties by name
+    let ranked = |side: &Side<'a>| -> Vec<(SegmentEnd<'a>, f32)> {
+        let mut ranked: Vec<_> = side.iter().map(|(e, c)| (*e, *c)).collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked
+    };
+    let distinct = |ranked: &[(SegmentEnd, f32)]| {
+        ranked
+            .windows(2)
+            .all(|w| (w[0].1 - w[1].1).abs() > tolerance * w[0].1.max(w[1].1))
+    };
+
+    let mut assignment = HashMap::new();
+    let one_each = |side: bool, ranked: &[(SegmentEnd<'a>, f32)], a: &mut HashMap<_, _>| {
+        for (copy, (neighbour, _)) in ranked.iter().enumerate() {
+            a.insert((side, *neighbour), vec![copy]);
+        }
+    };
+    let all_copies = |side: bool, ranked: &[(SegmentEnd<'a>, f32)], a: &mut HashMap<_, _>| {
+        for (neighbour, _) in ranked {
+            a.insert((side, *neighbour), (0..copies).collect());
+        }
+    };
+
+    let (start, end) = (ranked(start), ranked(end));
+    match (start.len() == copies, end.len() == copies) {
+        (true, true) if distinct(&start) && distinct(&end) => {
+            one_each(false, &start, &mut assignment);
+            one_each(true, &end, &mut assignment);
+        }
+        (true, false) if end.len() <= 1 => {
+            one_each(false, &start, &mut assignment);
+            all_copies(true, &end, &mut assignment);
+        }
+        (false, true) if start.len() <= 1 => {
+            all_copies(false, &start, &mut assignment);
+            one_each(true, &end, &mut assignment);
+        }
+        _ => return None,
+    }
+    Some(assignment)
+}
+
+/// Unroll collapsed repeats in a GFA, duplicating segments by their estimated copy
+/// number, and sharing their links between the copies where coverage allows.
+///
+/// The unrolled GFA is printed to STDOUT, and optionally a TSV of each repeat, and
+/// whether (or why not) it was unrolled, is written to `--report`.
+///
+/// For example:
+/// ```bash
+/// gfatk unroll in.gfa | gfatk linear > out.fa
+/// ```
+pub fn unroll(matches: &clap::ArgMatches) -> Result<()> {
+    let gfa_file = matches.get_one::<PathBuf>("GFA");
+    let max_copies = *matches
+        .get_one::<usize>("max-copies")
+        .expect("defaulted by clap");
+    let tolerance = *matches
+        .get_one::<f32>("tolerance")
+        .expect("defaulted by clap");
+    let report_file = matches.get_one::<PathBuf>("report");
+
+    let gfa: GFAtk = match gfa_file {
+        Some(f) => {
+            let ext = f.extension();
+            match ext {
+                Some(e) => {
+                    if e == "gfa" {
+                        GFAtk(load_gfa(f)?)
+                    } else {
+                        bail!("Input is not a GFA.")
+                    }
+                }
+                None => bail!("Could not read file."),
+            }
+        }
+        None => match utils::is_stdin() {
+            true => GFAtk(load_gfa_stdin(std::io::stdin().lock())?),
+            false => bail!("No input from STDIN. Run `gfatk unroll -h` for help."),
+        },
+    };
+
+    let (unrolled, repeats) = unroll_gfa(&gfa, max_copies, tolerance)?;
+
+    let done = repeats.iter().filter(|r| r.outcome == "unrolled").count();
+    eprintln!(
+        "[+]\tUnrolled {} of {} repeat segment(s).",
+        done,
+        repeats.len()
+    );
+    if done > 0 && (!gfa.0.paths.is_empty() || !gfa.0.containments.is_empty()) {
+        eprintln!("[-]\tPath (P) and containment (C) lines through unrolled segments are removed.");
+    }
+
+    if let Some(report_file) = report_file {
+        let mut report = String::from("segment\tcoverage\tcopies\toutcome\tnames\n");
+        for r in &repeats {
+            report += &format!(
+                "{}\t{}\t{}\t{}\t{}\n",
+                String::from_utf8_lossy(&r.segment),
+                r.coverage,
+                r.copies,
+                r.outcome,
+                r.names
+                    .iter()
+                    .map(|n| String::from_utf8_lossy(n))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            );
+        }
+        fs::write(report_file, report)
+            .with_context(|| format!("Could not write {:?}", report_file.as_os_str()))?;
+    }
+
+    println!(
+        "{}",
+        gfa_string(&unrolled.0, SortOrder::from_matches(matches))
+    );
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use anyhow::{bail, Context, Result};
 use atty::Stream;
+use gfa::gfa::Orientation;
 use gfa::optfields::{OptField, OptFieldVal::*};
 use petgraph::graph::NodeIndex;
 use std::collections::HashMap;
@@ -96,6 +97,45 @@ pub fn parse_cigar(cigar: &[u8]) -> Result<usize> {
     }
 }
 
+/// Reverse a CIGAR string, giving the overlap of the mirror of a link.
+///
+/// The operations are reversed, and insertions and deletions swapped, e.g.
+/// `3M1I2M` becomes `2M1D3M`. A missing overlap (`*`) is unchanged.
+pub fn reverse_cigar(cigar: &[u8]) -> Result<Vec<u8>> {
+    if cigar == b"*" {
+        return Ok(cigar.to_vec());
+    }
+    let malformed = || format!("Malformed CIGAR string: {}", String::from_utf8_lossy(cigar));
+
+    let mut operations = Vec::new();
+    let mut start = 0;
+    for (index, c) in cigar.iter().enumerate() {
+        if c.is_ascii_digit() {
+            continue;
+        }
+        let operation = match c {
+            b'I' => b'D',
+            b'D' => b'I',
+            b'M' | b'N' | b'S' | b'H' | b'P' | b'=' | b'X' => *c,
+            _ => bail!(malformed()),
+        };
+        if index == start {
+            bail!(malformed());
+        }
+        operations.push((&cigar[start..index], operation));
+        start = index + 1;
+    }
+    if start != cigar.len() {
+        bail!(malformed());
+    }
+
+    Ok(operations
+        .iter()
+        .rev()
+        .flat_map(|(length, operation)| length.iter().copied().chain([*operation]))
+        .collect())
+}
+
 /// Reverse complement a string slice.
 pub fn reverse_complement(dna: &[u8]) -> Vec<u8> {
     let dna_vec = dna.to_vec();
@@ -108,6 +148,14 @@ pub fn reverse_complement(dna: &[u8]) -> Vec<u8> {
     revcomp
 }
 
+/// Switch an orientation to its opposite.
+pub fn reverse_orientation(orientation: Orientation) -> Orientation {
+    match orientation {
+        Orientation::Forward => Orientation::Backward,
+        Orientation::Backward => Orientation::Forward,
+    }
+}
+
 /// Used in `reverse_complement` to switch to a complementary base.
 fn switch_base(c: u8) -> u8 {
     match c {
@@ -151,6 +199,52 @@ pub fn gc_content(dna: &[u8]) -> f32 {
     (g_counts + c_counts) as f32 / (g_counts + c_counts + a_counts + t_counts) as f32
 }
 
+/// Calculate the median of a set of values, each weighted by a length.
+///
+/// Used to estimate the single copy coverage of a set of segments, where
+/// long segments should count for more than short ones.
+pub fn weighted_median(values: &[(f32, usize)]) -> Option<f32> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let total: usize = sorted.iter().map(|(_, w)| w).sum();
+    let mut cumulative = 0;
+    for (value, weight) in sorted {
+        cumulative += weight;
+        if cumulative * 2 >= total {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Compare two segment names, with runs of digits compared as numbers.
+///
+/// So `2` sorts before `10`, and `utg9` before `utg10`.
+pub fn natural_cmp(a: &[u8], b: &[u8]) -> std::cmp::Ordering {
+    // split a name into runs of digits and non-digits
+    fn chunks(name: &[u8]) -> Vec<&[u8]> {
+        name.chunk_by(|x, y| x.is_ascii_digit() == y.is_ascii_digit())
+            .collect()
+    }
+
+    for (x, y) in chunks(a).into_iter().zip(chunks(b)) {
+        let ordering = match (x[0].is_ascii_digit(), y[0].is_ascii_digit()) {
+            (true, true) => {
+                let trim = |n: &[u8]| -> usize { n.iter().take_while(|d| **d == b'0').count() };
+                let (xt, yt) = (&x[trim(x)..], &y[trim(y)..]);
+                xt.len().cmp(&yt.len()).then(xt.cmp(yt))
+            }
+            _ => x.cmp(y),
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+    // fall back to bytes, for equal numbers written differently (e.g. `01` and `1`)
+    a.len().cmp(&b.len()).then(a.cmp(b))
+}
+
 // convert Node Index to segment ID and vice versa
 // I rely a lot on this tuple:
 // (NodeIndex, usize)
@@ -215,6 +309,12 @@ impl GFAGraphLookups {
     }
 }
 
+impl Default for GFAGraphLookups {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl fmt::Display for GFAGraphLookups {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut output = String::new();
@@ -271,6 +371,22 @@ mod tests {
         assert_eq!(gc, 0.5);
     }
 
+    #[test]
+    fn test_weighted_median() {
+        // the long segment dominates
+        let values = vec![(30.0, 40), (60.0, 10), (30.0, 20)];
+        assert_eq!(weighted_median(&values), Some(30.0));
+        assert_eq!(weighted_median(&[]), None);
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        let mut names: Vec<&[u8]> = vec![b"utg10", b"10", b"utg9", b"2", b"b", b"a1"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        let expected: Vec<&[u8]> = vec![b"2", b"10", b"a1", b"b", b"utg9", b"utg10"];
+        assert_eq!(names, expected);
+    }
+
     #[test]
     fn test_cigar_parse() {
         let cigar_ok = "120M".as_bytes();
@@ -282,4 +398,14 @@ mod tests {
         assert!(parsed_cigar);
         assert!(parsed_cigar2);
     }
+
+    #[test]
+    fn test_reverse_cigar() {
+        assert_eq!(reverse_cigar(b"120M").unwrap(), b"120M");
+        assert_eq!(reverse_cigar(b"3M1I2M").unwrap(), b"2M1D3M");
+        assert_eq!(reverse_cigar(b"30M10D20M5I10M").unwrap(), b"10M5D20M10I30M");
+        assert_eq!(reverse_cigar(b"*").unwrap(), b"*");
+        assert!(reverse_cigar(b"10").is_err());
+        assert!(reverse_cigar(b"M").is_err());
+    }
 }
@@ -147,18 +147,19 @@ fn test_subgraph_extraction() -> Result<(), Box<dyn std::error::Error>> {
         .arg("-s")
         .arg("11");
 
-    // should be the same output as ./tests/test_linear.gfa
+    // should be the same output as ./tests/test_linear.gfa, in the default
+    // order (links sorted by their segments)
     cmd.assert().stdout(predicate::str::contains(
         "H	VN:Z:1.0
 S	11	ACCTT	ll:f:30
 S	12	TCAAGG	ll:f:60
 S	13	CTTGATT	ll:f:30
 L	11	+	12	-	4M	ec:i:1
-L	12	-	13	+	5M	ec:i:1
 L	11	+	13	+	3M	ec:i:1
 L	12	+	11	-	4M	ec:i:1
-L	13	-	12	+	5M	ec:i:1
+L	12	-	13	+	5M	ec:i:1
 L	13	-	11	-	3M	ec:i:1
+L	13	-	12	+	5M	ec:i:1
 ",
     ));
 
@@ -175,7 +176,9 @@ fn test_subgraph_extraction_multiple() -> Result<(), Box<dyn std::error::Error>>
     cmd.arg("extract")
         .arg("./tests/test_subgraphs.gfa")
         .arg("-s")
-        .arg("11,14");
+        .arg("11,14")
+        .arg("--order")
+        .arg("input");
 
     cmd.assert().stdout(predicate::str::starts_with(
         "H	VN:Z:1.0
@@ -203,6 +206,52 @@ L	16	-	14	-	3M	ec:i:1
     Ok(())
 }
 
+// test `gfatk extract` with a distance
+//
+// H	VN:Z:1.0
+// S	1	<10bp>	ll:f:30.0
+// S	2	<100bp>	ll:f:30.0
+// S	3	<10bp>	ll:f:30.0
+// S	4	<10bp>	ll:f:5.0
+// L	1	+	2	+	0M	ec:i:30
+// L	2	+	3	+	0M	ec:i:30
+// L	3	+	4	+	0M	ec:i:5
+// (plus the mirror of each link)
+//
+// segment 2 starts 10bp from segment 1, but segment 3 starts
+// 110bp away. Segment 4 is at low coverage.
+
+#[test]
+fn test_subgraph_extraction_distance() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("extract")
+        .arg("./tests/test_distance.gfa")
+        .arg("-s")
+        .arg("1")
+        .arg("-d")
+        .arg("50");
+
+    cmd.assert()
+        .stdout(predicate::str::contains("S\t2\t").and(predicate::str::contains("S\t3\t").not()));
+
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("extract")
+        .arg("./tests/test_distance.gfa")
+        .arg("-s")
+        .arg("1")
+        .arg("-d")
+        .arg("150")
+        .arg("--min-coverage")
+        .arg("10");
+
+    cmd.assert()
+        .stdout(predicate::str::contains("S\t3\t").and(predicate::str::contains("S\t4\t").not()));
+
+    Ok(())
+}
+
 // test `gfatk trim`
 
 // H	VN:Z:1.0
@@ -431,3 +480,1228 @@ AATCAAGGT
 
     Ok(())
 }
+
+// test `gfatk extract-mito` with marker genes
+//
+// H	VN:Z:1.0
+// S	1	ACGTTGCAAGGCTTAGCATG	ll:f:30.0
+// S	2	TTGACCGATGCAATCGGTAC	ll:f:30.0
+// S	3	GGATCCTTAAGCGCTAATTC	ll:f:30.0
+// S	4	CATGGTACCAAGTTCGAAGC	ll:f:30.0
+// L	1	+	2	+	0M	ec:i:30
+// L	3	+	4	+	0M	ec:i:30
+// (plus the mirror of each link)
+//
+// both subgraphs pass the (relaxed) GC and size filters, but only
+// the first contains the marker (>cox1 GCAAGGCTTAGC).
+
+#[test]
+fn test_gfa_extract_mito_markers() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("extract-mito")
+        .arg("./tests/test_markers.gfa")
+        .arg("--size-lower")
+        .arg("0")
+        .arg("--gc-lower")
+        .arg("0")
+        .arg("--gc-upper")
+        .arg("1")
+        .arg("--markers")
+        .arg("./tests/test_markers.fa")
+        .arg("-k")
+        .arg("7");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("S\t1\t").and(predicate::str::contains("S\t3\t").not()));
+
+    Ok(())
+}
+
+// test `gfatk plastid-report`
+//
+// H	VN:Z:1.0
+// S	1	<40bp>	ll:f:30.0
+// S	2	<10bp>	ll:f:60.0
+// S	3	<20bp>	ll:f:30.0
+// L	1	+	2	+	0M	ec:i:30
+// L	2	+	3	+	0M	ec:i:30
+// L	3	+	2	-	0M	ec:i:30
+// L	2	-	1	+	0M	ec:i:30
+// (plus the mirror of each link)
+//
+// segment 2 is at twice the coverage of the others, and joins
+// them both, so it is the inverted repeat. Segment 1 is the
+// longer single copy region (LSC), segment 3 the shorter (SSC).
+
+#[test]
+fn test_gfa_plastid_report() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("plastid-report")
+        .arg("./tests/test_plastid.gfa")
+        .arg("--size-lower")
+        .arg("0")
+        .arg("--gc-lower")
+        .arg("0")
+        .arg("--gc-upper")
+        .arg("1");
+
+    cmd.assert().stdout(predicate::str::contains(
+        "0	LSC	40	30	1
+0	SSC	20	30	3
+0	IR	10	60	2
+",
+    ));
+
+    Ok(())
+}
+
+// test `gfatk extract-mito` with a preset from a preset file
+//
+// uses tests/test_markers.gfa (see `fn test_gfa_extract_mito_markers()`)
+// with a `tiny-mito` preset which lets both 40bp subgraphs through,
+// unless the size is overridden on the command line.
+
+#[test]
+fn test_gfa_extract_mito_preset_file() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("extract-mito")
+        .arg("./tests/test_markers.gfa")
+        .arg("--preset")
+        .arg("tiny-mito")
+        .arg("--preset-file")
+        .arg("./tests/test_presets.toml");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("S\t1\t").and(predicate::str::contains("S\t3\t")));
+
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("extract-mito")
+        .arg("./tests/test_markers.gfa")
+        .arg("--preset")
+        .arg("tiny-mito")
+        .arg("--preset-file")
+        .arg("./tests/test_presets.toml")
+        .arg("--size-upper")
+        .arg("10");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("No subgraphs within the bounds"));
+
+    Ok(())
+}
+
+// test `gfatk partition`
+//
+// H	VN:Z:1.0
+// S	1	GACGATTCATACAACTGATA	ll:f:50.0
+// S	2	CGTAGATGCGGGACCGAATG	ll:f:50.0
+// S	3	<60bp>	ll:f:100.0
+// S	4	<100bp>	ll:f:10.0
+// S	5	<100bp>	ll:f:10.0
+// S	6	GATTCCCAAGTTCCACAATCTAAGGGCTAC	ll:f:80.0
+// S	7	<50bp>	ll:f:10.0
+// L	1	+	2	+	0M	ec:i:50
+// L	2	-	1	-	0M	ec:i:50
+//
+// with the presets in tests/test_partition.toml, 1,2 (40bp) are mito,
+// 3 (60bp) is plastid, and 7 (50bp) is claimed by both. 4 and 5 set the
+// nuclear coverage (10), so 6 (8x) is left unassigned.
+
+#[test]
+fn test_gfa_partition() -> Result<(), Box<dyn std::error::Error>> {
+    let prefix = std::env::temp_dir().join("gfatk_test_partition");
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("partition")
+        .arg("./tests/test_partition.gfa")
+        .arg("--mito-preset")
+        .arg("tiny-mito")
+        .arg("--plastid-preset")
+        .arg("tiny-plastid")
+        .arg("--preset-file")
+        .arg("./tests/test_partition.toml")
+        .arg("--prefer")
+        .arg("plastid")
+        .arg("-o")
+        .arg(&prefix);
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("\tmito\t")
+            .and(predicate::str::contains("claimed by mito only\t1,2\n"))
+            .and(predicate::str::contains("claimed by plastid only\t3\n"))
+            .and(predicate::str::contains("tie, plastid preferred\t7\n"))
+            .and(predicate::str::contains("organelle-like\t6\n")),
+    );
+
+    let plastid = std::fs::read_to_string(prefix.with_extension("plastid.gfa"))?;
+    assert!(plastid.contains("S\t3\t") && plastid.contains("S\t7\t"));
+    let nuclear = std::fs::read_to_string(prefix.with_extension("nuclear.gfa"))?;
+    assert!(nuclear.contains("S\t4\t") && nuclear.contains("S\t5\t"));
+
+    Ok(())
+}
+
+// test `gfatk partition` with no nuclear coverage
+//
+// H	VN:Z:1.0
+// S	1	ACGTACGTAC	ll:f:0.0
+// S	2	GGCCAATT	ll:f:0.0
+// L	1	+	2	+	0M	ec:i:0
+//
+// the only unclaimed component has zero coverage, so there is nothing to compare
+// its coverage to, and it is nuclear.
+
+#[test]
+fn test_gfa_partition_no_coverage() -> Result<(), Box<dyn std::error::Error>> {
+    let prefix = std::env::temp_dir().join("gfatk_test_partition_no_coverage");
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("partition")
+        .arg("./tests/test_partition_no_coverage.gfa")
+        .arg("-o")
+        .arg(&prefix);
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("\tnuclear\t")
+            .and(predicate::str::contains(
+                "no nuclear coverage to compare to\t1,2\n",
+            ))
+            .and(predicate::str::contains("NaN").not())
+            .and(predicate::str::contains("inf").not()),
+    );
+
+    Ok(())
+}
+
+// test `gfatk stats` topology
+//
+// H	VN:Z:1.0
+// S	1	ACGTACGTAC	ll:f:30.0
+// S	2	AAGGC	ll:f:15.0
+// S	3	TTGCA	ll:f:15.0
+// S	4	GGCATGCATC	ll:f:30.0
+// S	5	ACG	ll:f:60.0
+// S	6	CCTA	ll:f:30.0
+// L	1	+	2	+	0M	ec:i:15
+// L	1	+	3	+	0M	ec:i:15
+// L	2	+	4	+	0M	ec:i:15
+// L	3	+	4	+	0M	ec:i:15
+// L	4	+	5	+	0M	ec:i:30
+// L	5	+	5	+	0M	ec:i:30
+// L	5	+	6	+	0M	ec:i:30
+// L	1	-	1	+	0M	ec:i:10
+//
+// 1 -> (2 | 3) -> 4 -> 5 (self loop) -> 6, and a hairpin on the start of 1,
+// which is one link on that end. 5 is the only repeat (two links on each end).
+
+#[test]
+fn test_gfa_stats_topology() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("stats").arg("./tests/test_topology.gfa").arg("-t");
+
+    // n50, l50, largest_segment, end_degrees, dead_ends, self_loops, hairpins,
+    // simple_bubbles, repeat_nodes
+    cmd.assert().success().stdout(predicate::str::ends_with(
+        "\tcomplex\t10\t2\t10\t0:1,1:7,2:4\t1\t1\t1\t1\t1\n",
+    ));
+
+    Ok(())
+}
+
+// test `gfatk stats --format json`
+//
+// uses tests/test_markers.gfa (see `fn test_gfa_extract_mito_markers()`),
+// which has two linear subgraphs, and checks the nested output parses.
+
+#[test]
+fn test_gfa_stats_json() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("stats")
+        .arg("./tests/test_markers.gfa")
+        .arg("--format")
+        .arg("json");
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&output)?;
+
+    let subgraphs = json.as_array().ok_or("expected a JSON array")?;
+    assert_eq!(subgraphs.len(), 2);
+    assert_eq!(subgraphs[0]["segments"], serde_json::json!(["1", "2"]));
+    // a link and its mirror make a linear subgraph, not a circular one
+    assert_eq!(subgraphs[0]["circularity"], "linear");
+    assert_eq!(subgraphs[0]["mitochondria"]["preset"], "plant-mito");
+    assert_eq!(subgraphs[0]["mitochondria"]["selected"], false);
+
+    Ok(())
+}
+
+// test `gfatk segments`
+//
+// H	VN:Z:1.0
+// S	1	ACGTACGTAC	ll:f:30.0
+// S	2	AAGGC	ll:f:15.0
+// S	3	TTGCA	ll:f:15.0
+// S	4	GGCATGCATC	ll:f:30.0
+// S	5	ACG	ll:f:60.0
+// S	6	CCTA	ll:f:30.0
+// L	1	+	2	+	0M	ec:i:15
+// L	1	+	3	+	0M	ec:i:15
+// L	2	+	4	+	0M	ec:i:15
+// L	3	+	4	+	0M	ec:i:15
+// L	4	+	5	+	0M	ec:i:30
+// L	5	+	5	+	0M	ec:i:30
+// L	5	+	6	+	0M	ec:i:30
+// L	1	-	1	+	0M	ec:i:10
+//
+// the component's coverage is 30, so 5 is at twice the copy number,
+// and 6 is the only tip.
+
+#[test]
+fn test_gfa_segments() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("segments")
+        .arg("./tests/test_topology.gfa")
+        .arg("--max-length")
+        .arg("5")
+        .arg("--sort")
+        .arg("coverage")
+        .arg("--descending");
+
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        "name\tlength\tgc\tcoverage\tcopy_number\tcomponent\tstart_links\tend_links\ttip
+5\t3\t0.6666667\t60\t2.00\t0\t2\t2\tfalse
+6\t4\t0.5\t30\t1.00\t0\t1\t0\ttrue
+",
+    ));
+
+    Ok(())
+}
+
+// test `gfatk segments` with no coverage
+
+// H	VN:Z:1.0
+// S	1	ACGT	ll:f:0
+// S	2	GGCC	ll:f:0
+// S	3	TTAA	ll:f:5
+// L	1	+	2	+	0M	ec:i:1
+// L	2	+	3	+	0M	ec:i:1
+
+// the length weighted median coverage of the component is 0, so there is no
+// copy number
+
+#[test]
+fn test_gfa_segments_no_coverage() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("segments")
+        .arg("./tests/test_segments_no_coverage.gfa");
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("3\t4\t0\t5\tNA\t0\t1\t0\ttrue\n")
+            .and(predicate::str::contains("NaN").not())
+            .and(predicate::str::contains("inf").not()),
+    );
+
+    Ok(())
+}
+
+// test `gfatk compact`
+//
+// H	VN:Z:1.0
+// S	1	AGCGTA	ll:f:30.0
+// S	2	TAACAG	ll:f:30.0
+// L	1	+	2	+	2M	ec:i:1
+// L	2	+	1	+	2M	ec:i:1
+//
+// 1 and 2 merge into a single circular segment, with the 2bp overlap
+// between them removed, and the closing link kept as a self loop.
+
+#[test]
+fn test_gfa_compact() -> Result<(), Box<dyn std::error::Error>> {
+    let mapping = std::env::temp_dir().join("gfatk_test_compact_mapping.tsv");
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("compact")
+        .arg("./tests/test_circular.gfa")
+        .arg("--mapping")
+        .arg(&mapping);
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("S\t1\tAGCGTAACAG\tll:f:30\n")
+            .and(predicate::str::contains("L\t1\t+\t1\t+\t2M\tec:i:1\n"))
+            .and(predicate::str::contains("S\t2\t").not()),
+    );
+
+    let mapping = std::fs::read_to_string(mapping)?;
+    assert!(mapping.contains("1\t10\t30\ttrue\t1+,2+\n"));
+
+    Ok(())
+}
+
+// test `gfatk bubbles`
+//
+// H	VN:Z:1.0
+// S	1	ACGTACGTAC	ll:f:30.0
+// S	2	AAGGC	ll:f:15.0
+// S	3	TTGCA	ll:f:15.0
+// S	4	GGCATGCATC	ll:f:30.0
+// S	5	ACG	ll:f:60.0
+// S	6	CCTA	ll:f:30.0
+// L	1	+	2	+	0M	ec:i:15
+// L	1	+	3	+	0M	ec:i:15
+// L	2	+	4	+	0M	ec:i:15
+// L	3	+	4	+	0M	ec:i:15
+// L	4	+	5	+	0M	ec:i:30
+// L	5	+	5	+	0M	ec:i:30
+// L	5	+	6	+	0M	ec:i:30
+// L	1	-	1	+	0M	ec:i:10
+//
+// 1 -> (2 | 3) -> 4, where 2 and 3 have equal coverage
+
+#[test]
+fn test_gfa_bubbles() -> Result<(), Box<dyn std::error::Error>> {
+    let popped = std::env::temp_dir().join("gfatk_test_bubbles_popped.gfa");
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("bubbles")
+        .arg("./tests/test_topology.gfa")
+        .arg("--pop")
+        .arg(&popped);
+
+    cmd.assert().success().stdout(predicate::str::contains(
+        "0\tsimple\t1+\t4+\t2+;3+\t5,5\t15,15\t2,3\n",
+    ));
+
+    let popped = std::fs::read_to_string(popped)?;
+    assert!(popped.contains("S\t2\t"));
+    assert!(!popped.contains("S\t3\t"));
+
+    Ok(())
+}
+
+// test `gfatk bubbles` segment order
+//
+// H	VN:Z:1.0
+// S	1	ACGTACGTAC	ll:f:30.0
+// S	2	AAGGC	ll:f:15.0
+// S	10	TTGCA	ll:f:15.0
+// S	4	GGCATGCATC	ll:f:30.0
+// L	1	+	2	+	0M	ec:i:15
+// L	1	+	10	+	0M	ec:i:15
+// L	2	+	4	+	0M	ec:i:15
+// L	10	+	4	+	0M	ec:i:15
+//
+// 1 -> (2 | 10) -> 4, where the interior segments are listed with 2 before 10
+
+#[test]
+fn test_gfa_bubbles_segment_order() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("bubbles").arg("./tests/test_bubbles_names.gfa");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::ends_with("\t2,10\n"));
+
+    Ok(())
+}
+
+// test `gfatk trim --tips`
+//
+// H	VN:Z:1.0
+// S	1	AAAAAAAAAA	ll:f:30
+// S	2	CCCCCCCCCC	ll:f:30
+// S	3	GGGGGGGGGGGG	ll:f:2
+// S	4	TT	ll:f:30
+// S	5	ACGTACG	ll:f:100
+// L	1	+	2	+	0M	ec:i:1
+// L	1	+	3	+	0M	ec:i:1
+// L	2	+	4	+	0M	ec:i:1
+// L	4	-	2	-	0M	ec:i:1
+// L	5	+	5	+	0M	ec:i:1
+//
+// 1 -> (2 -> 4 | 3), where 3 is a low coverage tip, and 5 is
+// a separate single segment circle
+
+#[test]
+fn test_gfa_trim_tips() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("trim")
+        .arg("./tests/test_tips.gfa")
+        .arg("--tips")
+        .arg("--max-tip-length")
+        .arg("5")
+        .arg("--dry-run");
+    cmd.assert().success().stdout(
+        predicate::str::contains("1\t3-\t12\t2\t30\tfalse\ttrue\n")
+            .and(predicate::str::contains("\t5").not()),
+    );
+
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("trim")
+        .arg("./tests/test_tips.gfa")
+        .arg("--tips")
+        .arg("--max-tip-length")
+        .arg("5");
+    cmd.assert().success().stdout(
+        predicate::str::contains("S\t3\t")
+            .not()
+            .and(predicate::str::contains("S\t1\t"))
+            .and(predicate::str::contains("S\t4\t"))
+            .and(predicate::str::contains("S\t5\t")),
+    );
+
+    Ok(())
+}
+
+// test `gfatk filter`
+//
+// H	VN:Z:1.0
+// S	1	ACGTACGTAC	ll:f:30.0
+// S	2	AAGGC	ll:f:15.0
+// S	3	TTGCA	ll:f:15.0
+// S	4	GGCATGCATC	ll:f:30.0
+// S	5	ACG	ll:f:60.0
+// S	6	CCTA	ll:f:30.0
+// L	1	+	2	+	0M	ec:i:15
+// L	1	+	3	+	0M	ec:i:15
+// L	2	+	4	+	0M	ec:i:15
+// L	3	+	4	+	0M	ec:i:15
+// L	4	+	5	+	0M	ec:i:30
+// L	5	+	5	+	0M	ec:i:30
+// L	5	+	6	+	0M	ec:i:30
+// L	1	-	1	+	0M	ec:i:10
+//
+// segments 2 and 3 are at half the component coverage, and
+// the hairpin on 1 has the lowest link coverage
+
+#[test]
+fn test_gfa_filter() -> Result<(), Box<dyn std::error::Error>> {
+    let report = std::env::temp_dir().join("gfatk_test_filter_report.tsv");
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("filter")
+        .arg("./tests/test_topology.gfa")
+        .arg("--min-relative-coverage")
+        .arg("0.6")
+        .arg("--min-relative-link-coverage")
+        .arg("0.7")
+        .arg("--report")
+        .arg(&report);
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("S\t2\t")
+            .not()
+            .and(predicate::str::contains("S\t3\t").not())
+            .and(predicate::str::contains("L\t1\t-\t1\t+").not())
+            .and(predicate::str::contains("L\t4\t+\t5\t+\t0M\tec:i:30\n")),
+    );
+
+    let report = std::fs::read_to_string(report)?;
+    assert!(report.contains("segment\t2\t0\t15\t30\tmin-relative-coverage\n"));
+    assert!(report.contains("link\t1-,1+\t0\t10\t15\tmin-relative-link-coverage\n"));
+
+    Ok(())
+}
+
+// test `gfatk normalize`
+
+// H	VN:Z:1.0
+// S	1	ACGT	ll:f:10
+// S	2	GGCC	ll:f:10
+// S	3	TTAA	ll:f:10
+// L	1	+	2	+	0M	ec:i:5
+// L	2	-	1	-	0M	ec:i:7
+// L	2	+	3	+	0M	ec:i:4
+// L	2	+	3	+	0M	ec:i:4
+// L	3	-	2	-	0M	ec:i:4
+// L	3	+	1	+	0M	ec:i:3
+// L	1	-	1	+	0M	ec:i:2
+
+// 1+,2+ is mirrored with different coverages, 2+,3+ is written twice
+// and mirrored, 3+,1+ has no mirror, and 1-,1+ is a hairpin (its own mirror)
+
+#[test]
+fn test_gfa_normalize() -> Result<(), Box<dyn std::error::Error>> {
+    let report = std::env::temp_dir().join("gfatk_test_normalize_report.tsv");
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("normalize")
+        .arg("./tests/test_normalize.gfa")
+        .arg("--report")
+        .arg(&report)
+        .arg("--order")
+        .arg("input");
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("L\t1\t+\t2\t+\t0M\tec:i:7\nL\t2\t+\t3\t+\t0M\tec:i:4\nL\t3\t+\t1\t+\t0M\tec:i:3\nL\t1\t-\t1\t+\t0M\tec:i:2\n")
+            .and(predicate::str::contains("L\t2\t-\t1\t-").not()),
+    );
+
+    let report = std::fs::read_to_string(report)?;
+    assert!(report.contains("2+,3+\tduplicate\t\n"));
+    assert!(report.contains("1+,2+\tcoverage_mismatch\t5,7\n"));
+    assert!(report.contains("3+,1+\tmissing_mirror\t\n"));
+
+    // and in memory, for `gfatk stats`
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("stats")
+        .arg("./tests/test_normalize.gfa")
+        .arg("--normalize-links");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Number of edges/links: 4\n"));
+
+    Ok(())
+}
+
+// test `gfatk normalize --mirrors`
+
+// H	VN:Z:1.0
+// S	1	ACGTACGT	ll:f:10
+// S	2	GGCCAATT	ll:f:10
+// L	1	+	2	+	3M1I2M	ec:i:5
+
+// the added mirror of 1+,2+ has the overlap reversed, with the insertion
+// now a deletion
+
+#[test]
+fn test_gfa_normalize_mirrors() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("normalize")
+        .arg("./tests/test_normalize_cigar.gfa")
+        .arg("--mirrors")
+        .arg("--order")
+        .arg("input");
+
+    cmd.assert().success().stdout(predicate::str::contains(
+        "L\t1\t+\t2\t+\t3M1I2M\tec:i:5\nL\t2\t-\t1\t-\t2M1D3M\tec:i:5\n",
+    ));
+
+    Ok(())
+}
+
+// test `gfatk chop`
+
+// H	VN:Z:1.0
+// S	1	ACGTACGTAC	ll:f:30.0
+// S	2	GGGCCC	ll:f:20.0
+// L	1	+	2	+	2M	ec:i:10
+// L	2	-	1	-	2M	ec:i:10
+// L	2	+	1	+	0M	ec:i:5
+// L	1	-	2	-	0M	ec:i:5
+// P	p1	1+,2+	2M
+
+// chopped to at most 4bp, 1 is in three pieces and 2 in two
+
+#[test]
+fn test_gfa_chop() -> Result<(), Box<dyn std::error::Error>> {
+    let mapping = std::env::temp_dir().join("gfatk_test_chop_mapping.tsv");
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("chop")
+        .arg("./tests/test_chop.gfa")
+        .arg("--max-len")
+        .arg("4")
+        .arg("--mapping")
+        .arg(&mapping);
+
+    cmd.assert().success().stdout(
+        predicate::str::contains(
+            "S\t1_1\tACGT\tll:f:30\nS\t1_2\tACG\tll:f:30\nS\t1_3\tTAC\tll:f:30\n",
+        )
+        .and(predicate::str::contains("L\t1_1\t+\t1_2\t+\t0M\tec:i:30\n"))
+        .and(predicate::str::contains("L\t1_3\t+\t2_1\t+\t2M\tec:i:10\n"))
+        .and(predicate::str::contains("L\t1_1\t-\t2_2\t-\t0M\tec:i:5\n"))
+        .and(predicate::str::contains(
+            "P\tp1\t1_1+,1_2+,1_3+,2_1+,2_2+\t0M,0M,2M,0M\n",
+        )),
+    );
+
+    let mapping = std::fs::read_to_string(mapping)?;
+    assert!(mapping.contains("1\t1_3\t7\t10\n"));
+    assert!(mapping.contains("2\t2_2\t3\t6\n"));
+
+    Ok(())
+}
+
+// test `gfatk chop` with a duplicate link
+
+// H	VN:Z:1.0
+// S	1	ACGTACGTAC	ll:f:30.0
+// S	2	GGGCCC	ll:f:20.0
+// L	1	+	2	+	0M	ec:i:10
+// L	1	+	2	+	0M	ec:i:10
+
+// 1+,2+ is written twice but has no mirror, so the joins of the pieces
+// are not mirrored either
+
+#[test]
+fn test_gfa_chop_duplicate_links() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("chop")
+        .arg("./tests/test_chop_duplicates.gfa")
+        .arg("--max-len")
+        .arg("4");
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("L\t1_1\t+\t1_2\t+\t0M\tec:i:30\n")
+            .and(predicate::str::contains("L\t1_2\t-\t1_1\t-").not()),
+    );
+
+    Ok(())
+}
+
+// test `gfatk merge`
+
+// test_merge_k31.gfa
+// H	VN:Z:1.0
+// S	1	AAAC	ll:f:10
+// S	2	GGGT	ll:f:10
+// L	1	+	2	+	0M	ec:i:3
+// P	p	1+,2+	0M
+
+// test_merge_k51.gfa
+// H	VN:Z:1.0
+// S	1	ACCC	ll:f:20
+// S	2	TTTT	ll:f:20
+// L	1	+	2	+	0M	ec:i:4
+// P	p	1+,2+	0M
+
+// segment 1 of the second is the reverse complement of segment 2 of the first
+
+#[test]
+fn test_gfa_merge() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("merge")
+        .arg("./tests/test_merge_k31.gfa")
+        .arg("./tests/test_merge_k51.gfa");
+
+    cmd.assert().success().stdout(
+        predicate::str::starts_with(
+            "H\tVN:Z:1.0\nH\tfn:Z:./tests/test_merge_k31.gfa\nH\tfn:Z:./tests/test_merge_k51.gfa\n",
+        )
+        .and(predicate::str::contains(
+            "S\ttest_merge_k51_1\tACCC\tll:f:20\n",
+        ))
+        .and(predicate::str::contains(
+            "L\ttest_merge_k51_1\t+\ttest_merge_k51_2\t+\t0M\tec:i:4\n",
+        ))
+        .and(predicate::str::contains(
+            "P\ttest_merge_k51_p\ttest_merge_k51_1+,test_merge_k51_2+\t0M\n",
+        )),
+    );
+
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("merge")
+        .arg("./tests/test_merge_k31.gfa")
+        .arg("./tests/test_merge_k51.gfa")
+        .arg("--dedup")
+        .arg("reverse-complement")
+        .arg("--rename")
+        .arg("renumber");
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("S\t3\tTTTT\tll:f:20\n")
+            .and(predicate::str::contains("ACCC").not())
+            .and(predicate::str::contains("L\t2\t-\t3\t+\t0M\tec:i:4\n"))
+            .and(predicate::str::contains("P\tp\t1+,2+\t0M\n"))
+            .and(predicate::str::contains("P\ttest_merge_k51_p\t2-,3+\t0M\n")),
+    );
+
+    Ok(())
+}
+
+// test `gfatk diff`
+
+// test_diff_a.gfa
+// H	VN:Z:1.0
+// S	1	AAAA	ll:f:10
+// S	2	CCCC	ll:f:10
+// S	3	GGGA	ll:f:10
+// S	4	TTTA	ll:f:10
+// L	1	+	2	+	0M	ec:i:5
+// L	2	-	1	-	0M	ec:i:5
+// L	2	+	3	+	0M	ec:i:5
+// L	3	-	2	-	0M	ec:i:5
+
+// test_diff_b.gfa
+// H	VN:Z:1.0
+// S	1	AAAA	ll:f:30
+// S	2	CCCG	ll:f:10
+// S	4	TTTA	ll:f:10
+// S	5	ACGT	ll:f:10
+// L	2	+	4	+	0M	ec:i:5
+// L	1	+	5	+	0M	ec:i:5
+
+// test_diff_renamed.gfa
+// H	VN:Z:1.0
+// S	1	AAAA	ll:f:10
+// S	20	CCCC	ll:f:10
+// S	30	TCCC	ll:f:10
+// S	4	TTTA	ll:f:10
+// L	1	+	20	+	0M	ec:i:5
+// L	20	-	1	-	0M	ec:i:5
+// L	20	+	30	-	0M	ec:i:5
+// L	30	+	20	-	0M	ec:i:5
+
+// component 0 (1, 2, 3) is split, and 2 joins 4. In test_diff_renamed.gfa, 2 is
+// renamed 20, and 3 is reverse complemented as 30, with the same links
+
+#[test]
+fn test_gfa_diff() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("diff")
+        .arg("./tests/test_diff_a.gfa")
+        .arg("./tests/test_diff_b.gfa");
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("sequence_changed\t2\t4bp, C at 4\t4bp, G at 4\n")
+            .and(predicate::str::contains("segment_removed\t3\t4bp\t\n"))
+            .and(predicate::str::contains("segment_added\t5\t\t4bp\n"))
+            .and(predicate::str::contains("coverage_changed\t1\t10\t30\n"))
+            .and(predicate::str::contains("link_removed\t1+,2+\t1+,2+\t\n"))
+            .and(predicate::str::contains("link_added\t2+,4+\t\t2+,4+\n"))
+            .and(predicate::str::contains("component_split\t0\t0\t0,1\n"))
+            .and(predicate::str::contains("component_merged\t1\t0,1\t1\n")),
+    );
+
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("diff")
+        .arg("./tests/test_diff_a.gfa")
+        .arg("./tests/test_diff_renamed.gfa");
+
+    cmd.assert().success().stdout(predicate::eq(
+        "change\telement\ta\tb\n\
+         segment_renamed\t20\t2+\t20+\n\
+         segment_renamed\t30\t3-\t30+\n",
+    ));
+
+    Ok(())
+}
+
+// test component numbering in `gfatk filter` and `gfatk diff`
+
+// test_component_order.gfa
+// H	VN:Z:1.0
+// S	10	ACGT	ll:f:10
+// S	2	CCCC	ll:f:10
+// S	3	GGGG	ll:f:2
+// L	2	+	3	+	0M	ec:i:5
+
+// test_component_order_split.gfa is the same, without the link
+
+// segments are written out of name order, but components are numbered as in
+// `gfatk stats`, so 2 and 3 are component 0
+
+#[test]
+fn test_component_numbering() -> Result<(), Box<dyn std::error::Error>> {
+    let report = std::env::temp_dir().join("gfatk_test_component_order_report.tsv");
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("filter")
+        .arg("./tests/test_component_order.gfa")
+        .arg("--min-coverage")
+        .arg("5")
+        .arg("--report")
+        .arg(&report);
+
+    cmd.assert().success();
+
+    let report = std::fs::read_to_string(report)?;
+    assert!(report.contains("segment\t3\t0\t2\t2\tmin-coverage\n"));
+
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("diff")
+        .arg("./tests/test_component_order.gfa")
+        .arg("./tests/test_component_order_split.gfa");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("component_split\t0\t0\t0,1\n"));
+
+    Ok(())
+}
+
+// test `--order`
+
+// H	VN:Z:1.0
+// S	utg3	A	ll:f:10
+// S	10	AAAAAA	ll:f:10
+// S	2	AC	ll:f:10
+// S	1	ACG	ll:f:10
+// L	10	+	2	+	0M	ec:i:5
+// L	1	+	10	+	0M	ec:i:5
+
+// by name 1, 2, 10, utg3; from the longest 10, 1, 2, utg3;
+// and following the links 1, 10, 2, utg3
+
+#[test]
+fn test_gfa_order() -> Result<(), Box<dyn std::error::Error>> {
+    let report = std::env::temp_dir().join("gfatk_test_order_report.tsv");
+
+    for (order, segments) in [
+        ("name", ["1", "2", "10", "utg3"]),
+        ("bfs", ["10", "1", "2", "utg3"]),
+        ("topological", ["1", "10", "2", "utg3"]),
+        ("input", ["utg3", "10", "2", "1"]),
+    ] {
+        let mut cmd = Command::cargo_bin("gfatk")?;
+
+        cmd.arg("filter")
+            .arg("./tests/test_order.gfa")
+            .arg("--report")
+            .arg(&report)
+            .arg("--order")
+            .arg(order);
+
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let written: Vec<String> = String::from_utf8(output)?
+            .lines()
+            .filter(|l| l.starts_with('S'))
+            .map(|l| l.split('\t').nth(1).unwrap_or_default().to_string())
+            .collect();
+        assert_eq!(written, segments, "--order {}", order);
+    }
+
+    Ok(())
+}
+
+// test `gfatk unroll`
+
+// H	VN:Z:1.0
+// S	A	AAAC	ll:f:11
+// S	B	AAAG	ll:f:9
+// S	R	CCCC	ll:f:20
+// S	C	GGGA	ll:f:11
+// S	D	GGGT	ll:f:9
+// S	E	TTTA	ll:f:10
+// S	S	TTTC	ll:f:20
+// S	F	ACAC	ll:f:10
+// S	G	AGAG	ll:f:10
+// S	H	CACA	ll:f:10
+// S	I	CTCT	ll:f:10
+// S	T	GAGA	ll:f:20
+// S	J	GTGT	ll:f:10
+// S	K	TATA	ll:f:10
+// L	A	+	R	+	0M	ec:i:11
+// L	B	+	R	+	0M	ec:i:9
+// L	R	+	C	+	0M	ec:i:11
+// L	R	+	D	+	0M	ec:i:9
+// L	E	+	S	+	0M	ec:i:10
+// L	S	+	F	+	0M	ec:i:10
+// L	S	+	G	+	0M	ec:i:10
+// L	H	+	T	+	0M	ec:i:10
+// L	I	+	T	+	0M	ec:i:10
+// L	T	+	J	+	0M	ec:i:10
+// L	T	+	K	+	0M	ec:i:10
+
+// R is between A and B (coverage 11 and 9) and C and D (11 and 9), so is unrolled
+// into A -> R_1 -> C and B -> R_2 -> D. S has one neighbour at its start, so both
+// copies follow E, sharing the coverage of that link. The neighbours of T have equal coverage, so T is left.
+
+#[test]
+fn test_gfa_unroll() -> Result<(), Box<dyn std::error::Error>> {
+    let report = std::env::temp_dir().join("gfatk_test_unroll_report.tsv");
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("unroll")
+        .arg("./tests/test_unroll.gfa")
+        .arg("--tolerance")
+        .arg("0.1")
+        .arg("--report")
+        .arg(&report);
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("S\tR_1\tCCCC\tll:f:10\n")
+            .and(predicate::str::contains("L\tA\t+\tR_1\t+\t0M\tec:i:11\n"))
+            .and(predicate::str::contains("L\tR_1\t+\tC\t+\t0M\tec:i:11\n"))
+            .and(predicate::str::contains("L\tB\t+\tR_2\t+\t0M\tec:i:9\n"))
+            .and(predicate::str::contains("L\tR_2\t+\tD\t+\t0M\tec:i:9\n"))
+            .and(predicate::str::contains("L\tE\t+\tS_1\t+\t0M\tec:i:5\n"))
+            .and(predicate::str::contains("L\tE\t+\tS_2\t+\t0M\tec:i:5\n"))
+            .and(predicate::str::contains("L\tS_2\t+\tG\t+\t0M\tec:i:10\n"))
+            .and(predicate::str::contains("S\tT\tGAGA\tll:f:20\n")),
+    );
+
+    let report = std::fs::read_to_string(report)?;
+    assert!(report.contains("R\t20\t2\tunrolled\tR_1,R_2\n"));
+    assert!(report.contains("T\t20\t2\tambiguous\t\n"));
+
+    Ok(())
+}
+
+// test `gfatk orient`
+
+// H	VN:Z:1.0
+// S	1	ACGTACGT	ll:f:10
+// S	2	AAC	ll:f:10
+// S	3	GGT	ll:f:10
+// S	4	TTA	ll:f:10
+// S	5	AAAAAAAAAA	ll:f:10
+// S	6	CC	ll:f:10
+// S	7	GG	ll:f:10
+// L	1	+	2	-	0M	ec:i:5
+// L	2	+	1	-	0M	ec:i:5
+// L	2	-	3	+	0M	ec:i:5
+// L	3	-	2	+	0M	ec:i:5
+// L	5	+	6	-	1M1D1M	ec:i:5
+// L	7	+	5	-	0M	ec:i:5
+// P	p	1+,2-,3+	0M,0M
+
+// 2 is flipped to follow 1 (the longest segment). 6 and 7 disagree with 5, so
+// 5 alone is flipped, and 5-,6- is written as its mirror with the overlap reversed.
+
+#[test]
+fn test_gfa_orient() -> Result<(), Box<dyn std::error::Error>> {
+    let report = std::env::temp_dir().join("gfatk_test_orient_report.tsv");
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("orient")
+        .arg("./tests/test_orient.gfa")
+        .arg("--report")
+        .arg(&report);
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("S\t2\tGTT\tll:f:10\n")
+            .and(predicate::str::contains("S\t5\tTTTTTTTTTT\tll:f:10\n"))
+            .and(predicate::str::contains("L\t1\t+\t2\t+\t0M\tec:i:5\n"))
+            .and(predicate::str::contains("L\t2\t-\t1\t-\t0M\tec:i:5\n"))
+            .and(predicate::str::contains("L\t2\t+\t3\t+\t0M\tec:i:5\n"))
+            .and(predicate::str::contains("L\t6\t+\t5\t+\t1M1I1M\tec:i:5\n"))
+            .and(predicate::str::contains("L\t7\t+\t5\t+\t0M\tec:i:5\n"))
+            .and(predicate::str::contains("P\tp\t1+,2+,3+\t0M,0M\n")),
+    );
+
+    let report = std::fs::read_to_string(report)?;
+    assert_eq!(report, "segment\tcomponent\n2\t0\n5\t2\n");
+
+    Ok(())
+}
+
+// test `gfatk cuts`
+
+// H	VN:Z:1.0
+// S	1	AAAA	ll:f:10
+// S	2	CCCC	ll:f:10
+// S	3	GGGG	ll:f:10
+// S	4	TTTTTT	ll:f:30
+// S	5	AC	ll:f:10
+// S	6	AGA	ll:f:10
+// S	7	TCT	ll:f:10
+// L	1	+	2	+	0M	ec:i:5
+// L	2	+	3	+	0M	ec:i:5
+// L	3	+	1	+	0M	ec:i:5
+// L	3	+	4	+	1M	ec:i:2
+// L	4	-	3	-	1M	ec:i:2
+// L	4	+	5	+	0M	ec:i:5
+// L	6	+	7	+	0M	ec:i:5
+// L	6	+	7	-	0M	ec:i:5
+
+// the triangle 1, 2, 3 hangs off 3 -> 4 -> 5 (the link and its mirror are one
+// bridge). 6 and 7 are joined twice, so there is no bridge between them.
+
+#[test]
+fn test_gfa_cuts() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("cuts").arg("./tests/test_cuts.gfa");
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("0\tbridge\t3+,4+\t1\t2\t2\t3,2\t12,8\n")
+            .and(predicate::str::contains(
+                "0\tbridge\t4+,5+\t0\t5\t2\t4,1\t18,2\n",
+            ))
+            .and(predicate::str::contains(
+                "0\tarticulation\t3\t4\t10\t2\t2,2\t8,8\n",
+            ))
+            .and(predicate::str::contains(
+                "0\tarticulation\t4\t6\t30\t2\t3,1\t12,2\n",
+            ))
+            .and(predicate::str::contains("\n1\t").not()),
+    );
+
+    Ok(())
+}
+
+// test `gfatk shortest-path`
+
+// H	VN:Z:1.0
+// S	1	AAAAAA	ll:f:10
+// S	2	CCCC	ll:f:10
+// S	3	GGGGGGGG	ll:f:10
+// S	4	TTTTT	ll:f:10
+// S	5	ACAC	ll:f:10
+// L	1	+	2	+	1M	ec:i:5
+// L	2	+	4	+	2M	ec:i:5
+// L	3	+	1	-	0M	ec:i:5
+// L	4	-	3	+	1M	ec:i:5
+// L	4	+	5	+	0M	ec:i:5
+
+// 1+ reaches 4+ through 2+ (6 + 4 - 1 + 5 - 2 = 12bp), or through 3- (6 + 8 + 5 - 1
+// = 18bp), the second route written with the mirrors of its links. 5 leads nowhere.
+
+#[test]
+fn test_gfa_shortest_path() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("shortest-path")
+        .arg("1+")
+        .arg("4+")
+        .arg("./tests/test_shortest_path.gfa")
+        .arg("-k")
+        .arg("3");
+
+    cmd.assert().success().stdout(predicate::eq(
+        "rank\tlength\tsegments\tpath\n1\t12\t3\t1+,2+,4+\n2\t18\t3\t1+,3-,4+\n",
+    ));
+
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("shortest-path")
+        .arg("5+")
+        .arg("1+")
+        .arg("./tests/test_shortest_path.gfa");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("No walk found"));
+
+    Ok(())
+}
+
+// test `gfatk cycles`
+
+// H	VN:Z:1.0
+// S	1	AAAAAA	ll:f:10
+// S	2	CCCC	ll:f:20
+// S	3	GGGGG	ll:f:10
+// S	4	TTTTTTTT	ll:f:30
+// S	5	ACGTAC	ll:f:40
+// S	6	AGA	ll:f:10
+// L	1	+	2	+	1M	ec:i:7
+// L	2	+	3	+	0M	ec:i:5
+// L	3	-	2	-	0M	ec:i:5
+// L	3	+	1	+	2M	ec:i:6
+// L	2	+	4	-	0M	ec:i:3
+// L	3	-	4	+	0M	ec:i:4
+// L	5	+	5	+	1M	ec:i:9
+// L	6	+	6	-	0M	ec:i:2
+
+// 1+ -> 2+ -> 3+ closes back on 1+, either directly or through 4- (the link
+// 3- -> 4+ walked as its mirror). 3- -> 2- is the mirror of 2+ -> 3+, and adds
+// no cycle. 5 links to itself, and the hairpin on 6 is not a cycle.
+
+#[test]
+fn test_gfa_cycles() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("cycles").arg("./tests/test_cycles.gfa");
+
+    cmd.assert().success().stdout(predicate::eq(
+        "component\tlength\tsegments\tmin_link_coverage\tmean_coverage\tpath\n\
+         0\t20\t4\t3\t17.50\t1+,2+,4-,3+\n\
+         0\t12\t3\t5\t13.33\t1+,2+,3+\n\
+         1\t5\t1\t9\t40.00\t5+\n",
+    ));
+
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("cycles")
+        .arg("./tests/test_cycles.gfa")
+        .arg("--max-length")
+        .arg("15");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1+,2+,3+\n").and(predicate::str::contains("4-").not()));
+
+    Ok(())
+}
+
+// test `gfatk dedup`
+
+// H	VN:Z:1.0
+// S	1	ACGTT	ll:f:10
+// S	2	CCCCA	ll:f:10
+// S	3	AACGT	ll:f:12
+// S	4	ACGTT	ll:f:8
+// S	5	TGGGG	ll:f:10
+// S	6	GATC	ll:f:10
+// S	7	GATC	ll:f:10
+// L	1	+	2	+	0M	ec:i:5
+// L	3	-	5	-	0M	ec:i:4
+// L	2	+	6	+	0M	ec:i:5
+// L	4	+	7	+	0M	ec:i:3
+// L	5	+	4	-	0M	ec:i:2
+// P	p1	3+,5+	0M
+
+// 3 is the reverse complement of 1, and 4 a copy of it; 5 is the reverse complement
+// of 2; 6 and 7 are the same palindrome. 3- -> 5- is then 1+ -> 2+ again, and
+// 5+ -> 4- its mirror, so the three are one link with their coverages summed, as
+// are the coverages of the segments collapsed. The path through 3+ and 5+ walks 1-
+// and 2-.
+
+#[test]
+fn test_gfa_dedup() -> Result<(), Box<dyn std::error::Error>> {
+    let collapsed = std::env::temp_dir().join("gfatk_test_dedup_collapsed.gfa");
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("dedup")
+        .arg("./tests/test_dedup.gfa")
+        .arg("--collapse")
+        .arg(&collapsed);
+
+    cmd.assert().success().stdout(predicate::eq(
+        "group\tsegment\tkept\torientation\tlength\n\
+         0\t1\t1\t+\t5\n\
+         0\t3\t1\t-\t5\n\
+         0\t4\t1\t+\t5\n\
+         1\t2\t2\t+\t5\n\
+         1\t5\t2\t-\t5\n\
+         2\t6\t6\t+\t4\n\
+         2\t7\t6\t+\t4\n",
+    ));
+
+    let collapsed = std::fs::read_to_string(collapsed)?;
+    assert_eq!(collapsed.matches("\nS\t").count(), 3);
+    assert_eq!(collapsed.matches("\nL\t").count(), 3);
+    assert!(collapsed.contains("S\t1\tACGTT\tll:f:30\n"));
+    assert!(collapsed.contains("S\t6\tGATC\tll:f:20\n"));
+    assert!(collapsed.contains("L\t1\t+\t2\t+\t0M\tec:i:11\n"));
+    assert!(collapsed.contains("L\t1\t+\t6\t+\t0M\tec:i:3\n"));
+    assert!(collapsed.contains("P\tp1\t1-,2-\t0M"));
+
+    let mut cmd = Command::cargo_bin("gfatk")?;
+
+    cmd.arg("dedup")
+        .arg("./tests/test_dedup.gfa")
+        .arg("--same-strand");
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("0\t4\t1\t+\t5\n").and(predicate::str::contains("\t3\t").not()),
+    );
+
+    Ok(())
+}